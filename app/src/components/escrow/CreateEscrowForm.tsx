@@ -9,15 +9,38 @@ export const CreateEscrowForm = () => {
   
   const [recipient, setRecipient] = useState('');
   const [tokenMint, setTokenMint] = useState('');
+  const [arbitrator, setArbitrator] = useState('');
+  const [emergencySigners, setEmergencySigners] = useState('');
+  const [emergencyThreshold, setEmergencyThreshold] = useState('1');
   const [milestones, setMilestones] = useState([
     { amount: '', deadline: '', description: '' }
   ]);
   const [isSubmitting, setIsSubmitting] = useState(false);
 
+  // One signer address per line or separated by commas
+  const parseSigners = useCallback(
+    () => emergencySigners.split(/[\s,]+/).filter(Boolean),
+    [emergencySigners]
+  );
+
   const validateForm = useCallback(() => {
     if (!publicKey) return 'Wallet not connected';
     if (!recipient || !PublicKey.isOnCurve(recipient)) return 'Invalid recipient address';
     if (!tokenMint || !PublicKey.isOnCurve(tokenMint)) return 'Invalid token mint address';
+    if (!arbitrator || !PublicKey.isOnCurve(arbitrator)) return 'Invalid arbitrator address';
+    if (arbitrator === recipient || arbitrator === publicKey.toString()) {
+      return 'The arbitrator must be neither the creator nor the recipient';
+    }
+    
+    const signers = parseSigners();
+    if (signers.length === 0 || signers.length > 10) return 'Between 1 and 10 emergency signers are required';
+    if (signers.some(signer => !PublicKey.isOnCurve(signer))) return 'Invalid emergency signer address';
+    if (new Set(signers).size !== signers.length) return 'Emergency signers must be distinct';
+    
+    const threshold = Number(emergencyThreshold);
+    if (!Number.isInteger(threshold) || threshold < 1 || threshold > signers.length) {
+      return 'Emergency threshold must be between 1 and the number of signers';
+    }
     
     if (milestones.length === 0) return 'At least one milestone is required';
     
@@ -35,7 +58,7 @@ export const CreateEscrowForm = () => {
     }
     
     return null;
-  }, [publicKey, recipient, tokenMint, milestones]);
+  }, [publicKey, recipient, tokenMint, arbitrator, emergencyThreshold, parseSigners, milestones]);
 
   const handleAddMilestone = () => {
     setMilestones([...milestones, { amount: '', deadline: '', description: '' }]);
@@ -75,7 +98,10 @@ export const CreateEscrowForm = () => {
       const result = await createEscrow(
         recipient,
         milestoneData,
-        tokenMint
+        tokenMint,
+        arbitrator,
+        parseSigners(),
+        Number(emergencyThreshold)
       );
       
       console.log('Escrow created:', result);
@@ -84,6 +110,9 @@ export const CreateEscrowForm = () => {
       // Reset form
       setRecipient('');
       setTokenMint('');
+      setArbitrator('');
+      setEmergencySigners('');
+      setEmergencyThreshold('1');
       setMilestones([{ amount: '', deadline: '', description: '' }]);
     } catch (error) {
       console.error('Error creating escrow:', error);
@@ -126,6 +155,52 @@ export const CreateEscrowForm = () => {
           />
         </div>
         
+        <div>
+          <label className="block text-sm font-medium text-gray-700 mb-1">
+            Arbitrator Address
+          </label>
+          <input
+            type="text"
+            value={arbitrator}
+            onChange={(e) => setArbitrator(e.target.value)}
+            placeholder="Wallet that resolves milestone disputes"
+            className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+            disabled={isSubmitting || isLoading}
+          />
+        </div>
+        
+        <div className="grid grid-cols-1 md:grid-cols-3 gap-4">
+          <div className="md:col-span-2">
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Emergency Signers
+            </label>
+            <textarea
+              value={emergencySigners}
+              onChange={(e) => setEmergencySigners(e.target.value)}
+              placeholder="One wallet address per line (up to 10)"
+              className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              rows={3}
+              disabled={isSubmitting || isLoading}
+            />
+          </div>
+          
+          <div>
+            <label className="block text-sm font-medium text-gray-700 mb-1">
+              Signatures Required
+            </label>
+            <input
+              type="number"
+              min="1"
+              max="10"
+              step="1"
+              value={emergencyThreshold}
+              onChange={(e) => setEmergencyThreshold(e.target.value)}
+              className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
+              disabled={isSubmitting || isLoading}
+            />
+          </div>
+        </div>
+        
         <div className="space-y-4">
           <div className="flex justify-between items-center">
             <h3 className="text-lg font-medium">Milestones</h3>
@@ -2,7 +2,7 @@ import { useState, useEffect, useCallback } from 'react';
 import { PublicKey } from '@solana/web3.js';
 import { useEscrow } from '../../hooks/useEscrow';
 import { useWallet } from '@solana/wallet-adapter-react';
-import { formatDistanceToNow, format } from 'date-fns';
+import { format } from 'date-fns';
 
 interface EscrowDetailsProps {
   escrowAddress: string;
@@ -12,14 +12,14 @@ interface EscrowDetailsProps {
 
 export const EscrowDetails = ({ escrowAddress, tokenMint, onBack }: EscrowDetailsProps) => {
   const { publicKey } = useWallet();
-  const { getEscrow, completeMilestone, releaseFunds, isLoading } = useEscrow();
+  const { getEscrow, submitMilestone, completeMilestone, releaseFunds, isLoading } = useEscrow();
   
   const [escrow, setEscrow] = useState<any>(null);
   const [milestones, setMilestones] = useState<any[]>([]);
   const [isRefreshing, setIsRefreshing] = useState(false);
   const [activeTab, setActiveTab] = useState<'details' | 'milestones' | 'withdraw'>('details');
-  const [emergencySigners, setEmergencySigners] = useState(['', '', '']);
   const [isEmergencyMode, setIsEmergencyMode] = useState(false);
+  const [deliverableHash, setDeliverableHash] = useState('');
 
   const fetchEscrowData = useCallback(async () => {
     if (!escrowAddress) return;
@@ -56,6 +56,18 @@ export const EscrowDetails = ({ escrowAddress, tokenMint, onBack }: EscrowDetail
     fetchEscrowData();
   }, [fetchEscrowData]);
 
+  const handleSubmitMilestone = async (milestoneIndex: number) => {
+    if (!deliverableHash.trim()) return;
+    
+    try {
+      await submitMilestone(escrowAddress, milestoneIndex, deliverableHash.trim());
+      setDeliverableHash('');
+      await fetchEscrowData();
+    } catch (error) {
+      console.error('Failed to submit deliverable:', error);
+    }
+  };
+
   const handleCompleteMilestone = async (milestoneIndex: number) => {
     if (!confirm('Are you sure you want to mark this milestone as complete?')) return;
     
@@ -87,19 +99,9 @@ export const EscrowDetails = ({ escrowAddress, tokenMint, onBack }: EscrowDetail
     if (!confirm('Are you sure you want to initiate emergency withdrawal? This requires multi-signature.')) return;
     
     try {
-      const validSigners = emergencySigners
-        .filter(s => s.trim() !== '' && PublicKey.isOnCurve(s.trim()))
-        .map(s => new PublicKey(s.trim()));
-      
-      if (validSigners.length < 2) {
-        alert('At least 2 valid signer addresses are required for emergency withdrawal');
-        return;
-      }
-      
       await initiateEmergencyWithdrawal(
         escrowAddress,
-        escrow.creator,
-        validSigners.map(s => s.toString())
+        escrow.creator
       );
       
       alert('Emergency withdrawal initiated. Signers need to approve the withdrawal.');
@@ -256,10 +258,6 @@ export const EscrowDetails = ({ escrowAddress, tokenMint, onBack }: EscrowDetail
             <h3 className="text-sm font-medium text-gray-500">Created At</h3>
             <p>{new Date(escrow.createdAt).toLocaleString()}</p>
           </div>
-          <div>
-            <h3 className="text-sm font-medium text-gray-500">Last Updated</h3>
-            <p>{formatDistanceToNow(new Date(escrow.updatedAt), { addSuffix: true })}</p>
-          </div>
         </div>
       )}
       
@@ -269,8 +267,8 @@ export const EscrowDetails = ({ escrowAddress, tokenMint, onBack }: EscrowDetail
             <div
               key={index}
               className={`p-4 rounded-lg border ${
-                milestone.completedAt
-                  ? milestone.paidAt
+                milestone.verified
+                  ? index < escrow.currentMilestone
                     ? 'border-green-200 bg-green-50'
                     : 'border-yellow-200 bg-yellow-50'
                   : 'border-gray-200'
@@ -288,25 +286,48 @@ export const EscrowDetails = ({ escrowAddress, tokenMint, onBack }: EscrowDetail
                   )}
                 </div>
                 <div className="flex flex-col items-end space-y-2">
-                  {milestone.completedAt ? (
+                  {milestone.verified ? (
                     <span className="px-2 py-1 text-xs font-medium text-green-800 bg-green-100 rounded-full">
-                      Completed
+                      Verified on {format(new Date(milestone.verifiedAt), 'MMM d, yyyy')}
+                    </span>
+                  ) : milestone.completed ? (
+                    <span className="px-2 py-1 text-xs font-medium text-yellow-800 bg-yellow-100 rounded-full">
+                      Awaiting review
                     </span>
                   ) : (
                     <span className="px-2 py-1 text-xs font-medium text-yellow-800 bg-yellow-100 rounded-full">
                       Pending
                     </span>
                   )}
-                  {milestone.paidAt && (
+                  {index < escrow.currentMilestone && (
                     <span className="px-2 py-1 text-xs font-medium text-green-800 bg-green-100 rounded-full">
-                      Paid on {format(new Date(milestone.paidAt), 'MMM d, yyyy')}
+                      Settled
                     </span>
                   )}
                 </div>
               </div>
               
               <div className="mt-4 pt-4 border-t border-gray-100 flex justify-end space-x-2">
-                {isRecipient && !milestone.completedAt && (
+                {isRecipient && !milestone.completed && index === escrow.currentMilestone && (
+                  <>
+                    <input
+                      type="text"
+                      value={deliverableHash}
+                      onChange={(e) => setDeliverableHash(e.target.value)}
+                      placeholder="Deliverable hash (e.g. IPFS CID)"
+                      className="flex-1 px-3 py-1 text-sm border border-gray-300 rounded"
+                    />
+                    <button
+                      onClick={() => handleSubmitMilestone(index)}
+                      disabled={isLoading || !deliverableHash.trim()}
+                      className="px-3 py-1 text-sm bg-blue-500 text-white rounded hover:bg-blue-600 disabled:opacity-50"
+                    >
+                      Submit Deliverable
+                    </button>
+                  </>
+                )}
+                
+                {isCreator && milestone.completed && !milestone.verified && (
                   <button
                     onClick={() => handleCompleteMilestone(index)}
                     disabled={isLoading}
@@ -316,7 +337,7 @@ export const EscrowDetails = ({ escrowAddress, tokenMint, onBack }: EscrowDetail
                   </button>
                 )}
                 
-                {isCreator && milestone.completedAt && !milestone.paidAt && (
+                {isCreator && milestone.verified && index === escrow.currentMilestone && (
                   <button
                     onClick={() => handleReleaseFunds(index)}
                     disabled={isLoading}
@@ -343,7 +364,7 @@ export const EscrowDetails = ({ escrowAddress, tokenMint, onBack }: EscrowDetail
               <div className="ml-3">
                 <p className="text-sm text-yellow-700">
                   Emergency withdrawal allows the creator to recover funds in case of disputes or issues with the recipient. 
-                  This requires approval from at least 2 out of 3 signers.
+                  This requires approval from {escrow.emergencyThreshold} of the {escrow.emergencySigners.length} signers chosen when the escrow was created.
                 </p>
               </div>
             </div>
@@ -352,37 +373,23 @@ export const EscrowDetails = ({ escrowAddress, tokenMint, onBack }: EscrowDetail
           <div className="space-y-4">
             <h3 className="font-medium">Emergency Signers</h3>
             <p className="text-sm text-gray-600">
-              Enter at least 2 valid Solana addresses that will need to sign the emergency withdrawal.
+              These addresses can sign the emergency withdrawal.
             </p>
             
-            <div className="space-y-3">
-              {[0, 1, 2].map((i) => (
-                <div key={i}>
-                  <label className="block text-sm font-medium text-gray-700 mb-1">
-                    Signer {i + 1}
-                  </label>
-                  <input
-                    type="text"
-                    value={emergencySigners[i]}
-                    onChange={(e) => {
-                      const newSigners = [...emergencySigners];
-                      newSigners[i] = e.target.value;
-                      setEmergencySigners(newSigners);
-                    }}
-                    placeholder="Enter Solana address"
-                    className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500"
-                    disabled={isLoading}
-                  />
-                </div>
+            <ul className="space-y-2">
+              {escrow.emergencySigners.map((signer: any, i: number) => (
+                <li key={i} className="text-sm font-mono text-gray-700 break-all">
+                  {signer.toString()}
+                </li>
               ))}
-            </div>
+            </ul>
             
             <div className="pt-4">
               <button
                 onClick={handleInitiateEmergencyWithdrawal}
-                disabled={isLoading || emergencySigners.filter(s => s.trim() !== '').length < 2}
+                disabled={isLoading}
                 className={`px-4 py-2 rounded-md text-white font-medium ${
-                  isLoading || emergencySigners.filter(s => s.trim() !== '').length < 2
+                  isLoading
                     ? 'bg-gray-400 cursor-not-allowed'
                     : 'bg-yellow-600 hover:bg-yellow-700'
                 }`}
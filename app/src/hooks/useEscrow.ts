@@ -55,7 +55,10 @@ export const useEscrow = () => {
   const createEscrow = useCallback(async (
     recipient: string,
     milestones: Milestone[],
-    tokenMint: string
+    tokenMint: string,
+    arbitrator: string,
+    emergencySigners: string[],
+    emergencyThreshold: number
   ) => {
     if (!escrowClient || !publicKey) {
       throw new Error('Wallet not connected');
@@ -68,7 +71,13 @@ export const useEscrow = () => {
       // Initialize escrow
       const { escrow, tx: initTx } = await escrowClient.escrow.initializeEscrow(
         new PublicKey(recipient),
-        milestones.length
+        new PublicKey(tokenMint),
+        {
+          milestonesCount: milestones.length,
+          arbitrator: new PublicKey(arbitrator),
+          emergencySigners: emergencySigners.map(signer => new PublicKey(signer)),
+          emergencyThreshold,
+        }
       );
       
       showToast('success', 'Escrow initialized', `Transaction: ${initTx}`);
@@ -108,6 +117,39 @@ export const useEscrow = () => {
     }
   }, [escrowClient, publicKey, showToast]);
 
+  /**
+   * Submit the deliverable for a milestone
+   */
+  const submitMilestone = useCallback(async (
+    escrowAddress: string,
+    milestoneIndex: number,
+    deliverableHash: string
+  ) => {
+    if (!escrowClient || !publicKey) {
+      throw new Error('Wallet not connected');
+    }
+
+    try {
+      setIsLoading(true);
+      showToast('info', 'Submitting deliverable...');
+      
+      const tx = await escrowClient.escrow.submitMilestone(
+        new PublicKey(escrowAddress),
+        milestoneIndex,
+        Buffer.from(deliverableHash)
+      );
+      
+      showToast('success', 'Deliverable submitted', `Transaction: ${tx}`);
+      return tx;
+    } catch (error) {
+      console.error('Failed to submit deliverable:', error);
+      showToast('error', 'Failed to submit deliverable', error instanceof Error ? error.message : String(error));
+      throw error;
+    } finally {
+      setIsLoading(false);
+    }
+  }, [escrowClient, publicKey, showToast]);
+
   /**
    * Complete a milestone
    */
@@ -123,7 +165,7 @@ export const useEscrow = () => {
       setIsLoading(true);
       showToast('info', 'Completing milestone...');
       
-      const tx = await escrowClient.escrow.completeMilestone(
+      const tx = await escrowClient.escrow.approveMilestone(
         new PublicKey(escrowAddress),
         milestoneIndex
       );
@@ -179,8 +221,7 @@ export const useEscrow = () => {
    */
   const initiateEmergencyWithdrawal = useCallback(async (
     escrowAddress: string,
-    creator: string,
-    signers: string[]
+    creator: string
   ) => {
     if (!escrowClient || !publicKey) {
       throw new Error('Wallet not connected');
@@ -192,8 +233,7 @@ export const useEscrow = () => {
       
       const { multisig, tx } = await escrowClient.escrow.initiateEmergencyWithdrawal(
         new PublicKey(escrowAddress),
-        new PublicKey(creator),
-        signers.map(s => new PublicKey(s))
+        new PublicKey(creator)
       );
       
       showToast('success', 'Emergency withdrawal initiated', `Multisig: ${multisig.toString()}`);
@@ -251,11 +291,10 @@ export const useEscrow = () => {
         creator: escrow.creator.toString(),
         recipient: escrow.recipient.toString(),
         tokenMint: escrow.tokenMint.toString(),
-        tokenAccount: escrow.tokenAccount.toString(),
-        totalAmount: escrow.totalAmount.toNumber(),
+        tokenAccount: (await escrowClient.escrow.getEscrowTokenAccount(new PublicKey(escrowAddress))).toString(),
+        totalAmount: escrow.committedAmount.toNumber(),
         releasedAmount: escrow.releasedAmount.toNumber(),
         createdAt: new Date(escrow.createdAt.toNumber() * 1000),
-        updatedAt: new Date(escrow.updatedAt.toNumber() * 1000),
       };
     } catch (error) {
       console.error('Failed to get escrow:', error);
@@ -277,8 +316,8 @@ export const useEscrow = () => {
         ...milestone,
         amount: milestone.amount.toNumber(),
         deadline: new Date(milestone.deadline.toNumber() * 1000),
-        completedAt: milestone.completedAt ? new Date(milestone.completedAt.toNumber() * 1000) : null,
-        paidAt: milestone.paidAt ? new Date(milestone.paidAt.toNumber() * 1000) : null,
+        submittedAt: milestone.submittedAt ? new Date(milestone.submittedAt.toNumber() * 1000) : null,
+        verifiedAt: milestone.verifiedAt ? new Date(milestone.verifiedAt.toNumber() * 1000) : null,
       };
     } catch (error) {
       console.error('Failed to get milestone:', error);
@@ -291,6 +330,7 @@ export const useEscrow = () => {
     isInitializing,
     isLoading,
     createEscrow,
+    submitMilestone,
     completeMilestone,
     releaseFunds,
     initiateEmergencyWithdrawal,
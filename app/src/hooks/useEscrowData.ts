@@ -52,11 +52,10 @@ export const useEscrowData = (escrowAddress?: string) => {
         creator: escrowAccount.creator.toString(),
         recipient: escrowAccount.recipient.toString(),
         tokenMint: escrowAccount.tokenMint.toString(),
-        tokenAccount: escrowAccount.tokenAccount.toString(),
-        totalAmount: escrowAccount.totalAmount.toNumber(),
+        tokenAccount: (await dapprClient.escrow.getEscrowTokenAccount(new PublicKey(escrowAddress))).toString(),
+        totalAmount: escrowAccount.committedAmount.toNumber(),
         releasedAmount: escrowAccount.releasedAmount.toNumber(),
         createdAt: new Date(escrowAccount.createdAt.toNumber() * 1000),
-        updatedAt: new Date(escrowAccount.updatedAt.toNumber() * 1000),
       });
       
       // Fetch milestones
@@ -72,8 +71,9 @@ export const useEscrowData = (escrowAddress?: string) => {
               ...ms,
               amount: ms.amount.toNumber(),
               deadline: new Date(ms.deadline.toNumber() * 1000),
-              completedAt: ms.completedAt ? new Date(ms.completedAt.toNumber() * 1000) : null,
-              paidAt: ms.paidAt ? new Date(ms.paidAt.toNumber() * 1000) : null,
+              submittedAt: ms.submittedAt ? new Date(ms.submittedAt.toNumber() * 1000) : null,
+              verifiedAt: ms.verifiedAt ? new Date(ms.verifiedAt.toNumber() * 1000) : null,
+              released: ms.index < escrowAccount.currentMilestone,
             }))
         );
       }
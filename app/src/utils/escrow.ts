@@ -11,13 +11,22 @@ export const createEscrowWithMilestones = async (
   client: DapprClient,
   recipient: string,
   milestones: MilestoneInput[],
-  tokenMint: string
+  tokenMint: string,
+  arbitrator: string,
+  emergencySigners: string[],
+  emergencyThreshold: number
 ) => {
   try {
     // 1. Initialize the escrow
     const { escrow: escrowPubkey } = await client.escrow.initializeEscrow(
       new PublicKey(recipient),
-      milestones.length
+      new PublicKey(tokenMint),
+      {
+        milestonesCount: milestones.length,
+        arbitrator: new PublicKey(arbitrator),
+        emergencySigners: emergencySigners.map(signer => new PublicKey(signer)),
+        emergencyThreshold,
+      }
     );
 
     // 2. Add each milestone
@@ -60,8 +69,8 @@ export const completeAndReleaseMilestone = async (
   recipient: string
 ) => {
   try {
-    // 1. Mark milestone as complete
-    await client.escrow.completeMilestone(
+    // 1. Approve the submitted milestone
+    await client.escrow.approveMilestone(
       new PublicKey(escrowAddress),
       milestoneIndex
     );
@@ -86,14 +95,12 @@ export const completeAndReleaseMilestone = async (
 export const initiateEmergencyWithdrawalProcess = async (
   client: DapprClient,
   escrowAddress: string,
-  creator: string,
-  signers: string[]
+  creator: string
 ) => {
   try {
     const { multisig, tx } = await client.escrow.initiateEmergencyWithdrawal(
       new PublicKey(escrowAddress),
-      new PublicKey(creator),
-      signers.map(s => new PublicKey(s))
+      new PublicKey(creator)
     );
 
     return {
@@ -24,6 +24,19 @@ pub fn get_metadata_account(mint: &Pubkey) -> Pubkey {
     pda
 }
 
+// Derive the master edition account address for a given mint
+pub fn get_master_edition_account(mint: &Pubkey) -> Pubkey {
+    let metadata_program_id = Pubkey::from_str(TOKEN_METADATA_PROGRAM_ID).unwrap();
+    let seeds = &[
+        b"metadata".as_ref(),
+        metadata_program_id.as_ref(),
+        mint.as_ref(),
+        b"edition".as_ref(),
+    ];
+    let (pda, _bump) = Pubkey::find_program_address(seeds, &metadata_program_id);
+    pda
+}
+
 // Derive the mint authority PDA
 pub fn get_mint_authority(program_id: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[MINT_SEED], program_id)
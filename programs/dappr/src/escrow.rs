@@ -1,28 +1,284 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_lang::system_program;
+use anchor_spl::token::{spl_token::native_mint, Token};
+use anchor_spl::token_interface::{self, CloseAccount, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+use mpl_token_metadata::instruction::{create_master_edition_v3, create_metadata_accounts_v3};
 use crate::constants::*;
 use std::convert::TryInto;
 
 // Constants
 pub const ESCROW_PDA_SEED: &[u8] = b"escrow";
 pub const MILESTONE_PDA_SEED: &[u8] = b"milestone";
-pub const MULTISIG_THRESHOLD: u8 = 2; // 2 out of 3 multisig for emergency withdrawal
+pub const DISPUTE_PDA_SEED: &[u8] = b"dispute";
+pub const CONFIG_PDA_SEED: &[u8] = b"config";
+pub const CONTRIBUTION_PDA_SEED: &[u8] = b"contribution";
+pub const RECEIPT_MINT_SEED: &[u8] = b"receipt";
+pub const RECEIPT_NAME: &str = "Dappr Escrow Receipt";
+pub const RECEIPT_SYMBOL: &str = "DRCPT";
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000; // Protocol fee is capped at 10% of a release
+pub const MAX_ALLOWED_MINTS: usize = 10; // Upper bound on the escrow mint allow-list
+pub const MAX_EMERGENCY_SIGNERS: usize = 10; // Upper bound on the emergency withdrawal signer set
+pub const EMERGENCY_REQUEST_TTL: i64 = 7 * 24 * 60 * 60; // Emergency requests short of quorum lapse after 7 days
+pub const MAX_BASIS_POINTS: u16 = 10_000; // 100% expressed in basis points
+pub const MAX_VERIFIERS: usize = 5; // Upper bound on the milestone approver set
+pub const MAX_PAYEES: usize = 5; // Upper bound on the accounts a release is split between
+pub const MAX_DELIVERABLE_HASH_LEN: usize = 64; // Fits a binary IPFS CID
+// Statuses from which an escrow can still be cancelled or withdrawn
+pub const OPEN_ESCROW_STATUSES: &[EscrowStatus] = &[
+    EscrowStatus::Draft,
+    EscrowStatus::AwaitingFunding,
+    EscrowStatus::InProgress,
+    EscrowStatus::Disputed,
+];
+// Statuses in which an approved emergency withdrawal can pay out; a dispute must be ruled on first
+pub const WITHDRAWABLE_ESCROW_STATUSES: &[EscrowStatus] = &[
+    EscrowStatus::Draft,
+    EscrowStatus::AwaitingFunding,
+    EscrowStatus::InProgress,
+];
 
 // Account to store escrow state
 #[account]
 pub struct Escrow {
     pub creator: Pubkey,           // Creator of the escrow
-    pub recipient: Pubkey,          // Recipient of the funds
-    pub token_mint: Pubkey,         // Token mint (DAPPR_USD)
-    pub total_amount: u64,         // Total amount in the escrow
+    pub escrow_id: u64,            // Distinguishes escrows between the same creator and recipient
+    pub recipient: Pubkey,          // Current holder of the recipient's payment rights
+    pub initial_recipient: Pubkey,  // Recipient the escrow PDA was derived from; never changes
+    pub transfer_requires_creator: bool, // Recipient transfers also need the creator's signature
+    pub receipt_mint: Option<Pubkey>, // 1-of-1 token whose holder is paid the recipient's releases
+    pub payees: Vec<Payee>,         // How milestone releases are split (recipient alone by default)
+    pub arbitrator: Pubkey,         // Resolves disputes between creator and recipient
+    pub verifiers: Vec<Pubkey>,     // Who may approve milestones (creator by default)
+    pub verifier_threshold: u8,     // Approvals required to complete a milestone
+    pub review_period: i64,         // Seconds after submission before a milestone is deemed accepted
+    pub grace_period: i64,          // Seconds after a deadline before an unsubmitted milestone can be reclaimed
+    pub emergency_signers: Vec<Pubkey>, // Who may approve an emergency withdrawal
+    pub emergency_threshold: u8,    // Signatures required to execute an emergency withdrawal
+    pub emergency_delay: i64,       // Seconds the recipient has to object once an emergency withdrawal reaches quorum
+    pub token_mint: Pubkey,         // Allow-listed token mint, or the native mint for SOL escrows
+    pub is_native: bool,            // Holds lamports in the escrow PDA instead of a token account
+    pub funded_amount: u64,        // Total amount deposited into the escrow
+    pub amendment_refunds: u64,     // Deposits handed back because an amendment lowered the commitment below them
+    pub funding_status: FundingStatus, // Deposits measured against the committed amount
+    pub funding_deadline: Option<i64>, // Funders may claim refunds if the committed amount is not met by then
+    pub crowdfunded: bool,          // A wallet other than the creator has contributed
     pub released_amount: u64,       // Amount already released
+    pub refunded_amount: u64,       // Amount returned to the creator or claimed back by funders
+    pub refund_pool: u64,           // Unreleased funds set aside so far for funders to claim pro rata
+    pub refunds_claimed: u64,       // Portion of the refund pool funders have claimed
+    pub refunded_contributions: u64, // Contributions whose refunds have been claimed
     pub milestones_count: u8,       // Total number of milestones
+    pub milestones_added: u8,       // Milestones registered so far; next milestone index
+    pub committed_amount: u64,      // Sum of registered milestone amounts
     pub current_milestone: u8,      // Current milestone index (0-based)
-    pub is_active: bool,           // Whether the escrow is active
+    pub disputes_raised: u8,        // Dispute PDAs opened against the escrow's milestones
+    pub vesting: Option<VestingSchedule>, // Linear unlock schedule; None for milestone escrows
+    pub status: EscrowStatus,       // Lifecycle state of the escrow
+    pub cancel_proposed_by: Option<Pubkey>, // Party that proposed a mutual cancellation
+    pub cancel_recipient_share_bps: u16,    // Proposed share of the remaining balance for the recipient
     pub created_at: i64,            // Timestamp when escrow was created
     pub bump: u8,                   // Bump seed for the escrow PDA
 }
 
+// A share of every milestone release
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct Payee {
+    pub wallet: Pubkey,             // Owner of the associated token account paid
+    pub share_bps: u16,             // Share of each release in basis points
+}
+
+// Funds that unlock linearly between `start_ts` and `end_ts`, nothing before `cliff_ts`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct VestingSchedule {
+    pub amount: u64,                // Total amount vested over the schedule
+    pub start_ts: i64,              // When vesting begins
+    pub cliff_ts: i64,              // Nothing can be withdrawn before this timestamp
+    pub end_ts: i64,                // When the full amount has vested
+}
+
+impl VestingSchedule {
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        if now < self.cliff_ts {
+            return Ok(0);
+        }
+        if now >= self.end_ts {
+            return Ok(self.amount);
+        }
+        
+        let elapsed = now.saturating_sub(self.start_ts) as u128;
+        let duration = self.end_ts.saturating_sub(self.start_ts) as u128;
+        (self.amount as u128 * elapsed / duration)
+            .try_into()
+            .map_err(|_| error!(EscrowError::InvalidVestingSchedule))
+    }
+}
+
+// Terms an escrow is created with; see `InitializeEscrow::initialize` for the defaults
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeEscrowParams {
+    pub escrow_id: u64,             // Distinguishes escrows between the same creator and recipient
+    pub bump: u8,                   // Bump seed for the escrow PDA
+    pub milestones_count: u8,       // Milestones to be added; 0 for vesting escrows
+    pub arbitrator: Pubkey,         // Resolves disputes between creator and recipient
+    pub verifiers: Vec<Pubkey>,     // Who may approve milestones; empty for the creator alone
+    pub verifier_threshold: u8,     // Approvals required to complete a milestone
+    pub review_period: i64,         // Seconds after submission before a milestone is deemed accepted
+    pub grace_period: i64,          // Seconds after a deadline before an unsubmitted milestone can be reclaimed
+    pub emergency_signers: Vec<Pubkey>, // Who may approve an emergency withdrawal
+    pub emergency_threshold: u8,    // Signatures required to execute an emergency withdrawal
+    pub emergency_delay: i64,       // Seconds the recipient has to object once an emergency withdrawal reaches quorum
+    pub payees: Vec<Payee>,         // How releases are split; empty for the recipient alone
+    pub is_native: bool,            // Hold lamports in the escrow PDA instead of a token account
+    pub vesting: Option<VestingSchedule>, // Linear unlock schedule instead of milestones
+    pub funding_deadline: Option<i64>, // Funders may claim refunds if the escrow is not funded by then
+    pub transfer_requires_creator: bool, // Recipient transfers also need the creator's signature
+}
+
+// Lifecycle of an escrow
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum EscrowStatus {
+    Draft,              // Milestones are still being added
+    AwaitingFunding,    // Milestone schedule fixed, deposits outstanding
+    InProgress,         // Fully funded, milestones being worked on
+    Disputed,           // A milestone dispute awaits the arbitrator
+    Cancelled,          // Wound down by mutual agreement
+    EmergencyWithdrawn, // Remaining funds returned via the emergency multisig
+    Completed,          // Every milestone settled
+}
+
+impl EscrowStatus {
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            EscrowStatus::Cancelled | EscrowStatus::EmergencyWithdrawn | EscrowStatus::Completed
+        )
+    }
+    
+    // Transitions the program allows between lifecycle states
+    fn can_transition_to(self, next: EscrowStatus) -> bool {
+        use EscrowStatus::*;
+        
+        match (self, next) {
+            (Draft, AwaitingFunding | InProgress) => true,
+            (AwaitingFunding, InProgress) => true,
+            (InProgress, AwaitingFunding | Disputed | Completed) => true,
+            (Disputed, InProgress | Completed) => true,
+            (from, Cancelled | EmergencyWithdrawn) => !from.is_terminal(),
+            _ => false,
+        }
+    }
+    
+    // Error reported when an action is not allowed in this state
+    fn error(self) -> EscrowError {
+        match self {
+            EscrowStatus::Draft => EscrowError::EscrowNotFinalized,
+            EscrowStatus::AwaitingFunding => EscrowError::EscrowNotFullyFunded,
+            EscrowStatus::InProgress => EscrowError::EscrowInProgress,
+            EscrowStatus::Disputed => EscrowError::EscrowDisputed,
+            EscrowStatus::Cancelled => EscrowError::EscrowCancelled,
+            EscrowStatus::EmergencyWithdrawn => EscrowError::EscrowWithdrawn,
+            EscrowStatus::Completed => EscrowError::EscrowCompleted,
+        }
+    }
+}
+
+// How much of the committed milestone total has been deposited
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FundingStatus {
+    Unfunded,
+    PartiallyFunded,
+    FullyFunded,
+}
+
+impl Escrow {
+    // Every handler gates on and moves between lifecycle states through these two functions
+    pub fn require_status(&self, allowed: &[EscrowStatus]) -> Result<()> {
+        if allowed.contains(&self.status) {
+            Ok(())
+        } else {
+            Err(self.status.error().into())
+        }
+    }
+    
+    pub fn transition(&mut self, next: EscrowStatus) -> Result<()> {
+        if !self.status.can_transition_to(next) {
+            // A final state never moves again; any other refusal is a bug in the caller's gating
+            if self.status.is_terminal() {
+                return err!(EscrowError::EscrowNotActive);
+            }
+            return err!(EscrowError::InvalidStatusTransition);
+        }
+        
+        self.status = next;
+        Ok(())
+    }
+    
+    pub fn is_active(&self) -> bool {
+        !self.status.is_terminal()
+    }
+    
+    // Deposits still backing the milestones, measured against the committed amount
+    pub fn covered_amount(&self) -> u64 {
+        self.funded_amount.saturating_sub(self.amendment_refunds)
+    }
+    
+    // Refunds set aside for funders that are still held by the escrow
+    pub fn unclaimed_refunds(&self) -> u64 {
+        self.refund_pool.saturating_sub(self.refunds_claimed)
+    }
+    
+    // Wallet holding the recipient's payment rights: whoever owns the receipt once one is minted
+    pub fn rights_holder(
+        &self,
+        receipt_token_account: &Option<InterfaceAccount<'_, TokenAccount>>,
+    ) -> Result<Pubkey> {
+        match self.receipt_mint {
+            Some(receipt_mint) => {
+                let receipt = receipt_token_account
+                    .as_ref()
+                    .ok_or(EscrowError::InvalidReceiptAccount)?;
+                require!(
+                    receipt.mint == receipt_mint && receipt.amount == 1,
+                    EscrowError::InvalidReceiptAccount
+                );
+                Ok(receipt.owner)
+            }
+            None => Ok(self.recipient),
+        }
+    }
+    
+    // Splits an amount by payee share, giving the rounding dust to the first payee
+    pub fn split_among_payees(&self, amount: u64) -> Result<Vec<u64>> {
+        let mut shares = self.payees
+            .iter()
+            .map(|payee| {
+                (amount as u128 * payee.share_bps as u128 / MAX_BASIS_POINTS as u128)
+                    .try_into()
+                    .map_err(|_| error!(EscrowError::InvalidPayees))
+            })
+            .collect::<Result<Vec<u64>>>()?;
+        
+        let distributed: u64 = shares.iter().sum();
+        shares[0] = shares[0]
+            .checked_add(amount - distributed)
+            .ok_or(EscrowError::InvalidPayees)?;
+        
+        Ok(shares)
+    }
+}
+
+// Deposits made into an escrow by one funder
+#[account]
+pub struct Contribution {
+    pub escrow: Pubkey,             // Escrow the deposits were made into
+    pub funder: Pubkey,             // Wallet that made the deposits and may claim the refund
+    pub amount: u64,                // Total amount received from this funder
+    pub refunded: u64,              // Refunds claimed so far from the escrow's refund pool
+    pub bump: u8,                   // Bump seed for the contribution PDA
+}
+
 // Account to store milestone state
 #[account]
 pub struct Milestone {
@@ -30,24 +286,258 @@ pub struct Milestone {
     pub index: u8,                  // Milestone index (0-based)
     pub amount: u64,                // Amount allocated to this milestone
     pub deadline: i64,              // Unix timestamp for milestone deadline
-    pub completed: bool,            // Whether the milestone is completed
-    pub verified: bool,             // Whether the milestone is verified
+    pub completed: bool,            // Whether the recipient submitted the deliverable
+    pub verified: bool,             // Whether the submission was approved
+    pub deliverable_hash: Vec<u8>,  // Content hash of the submitted deliverable (e.g. IPFS CID)
+    pub submitted_at: Option<i64>,  // When the deliverable was submitted
+    pub rejection_code: Option<u16>, // Reason code of the last rejected submission
     pub verified_at: Option<i64>,   // When the milestone was verified
     pub verified_by: Option<Pubkey>, // Who verified the milestone
+    pub approvals: Vec<Pubkey>,     // Verifiers that have approved so far
+    pub disputed: bool,             // Whether a dispute is open on this milestone
+    pub pending_amendment: Option<MilestoneAmendment>, // Change awaiting the other party's consent
     pub bump: u8,                   // Bump seed for the milestone PDA
 }
 
+// A proposed change to a milestone's terms
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct MilestoneAmendment {
+    pub proposer: Pubkey,           // Party that proposed the change
+    pub amount: u64,                // Proposed milestone amount
+    pub deadline: i64,              // Proposed milestone deadline
+}
+
+impl Milestone {
+    // A submission left unreviewed past the review period is deemed accepted
+    pub fn review_period_elapsed(&self, review_period: i64, now: i64) -> bool {
+        match self.submitted_at {
+            Some(submitted_at) if self.completed && !self.verified => {
+                now >= submitted_at.saturating_add(review_period)
+            }
+            _ => false,
+        }
+    }
+}
+
+// Lifecycle of a milestone dispute
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeStatus {
+    Open,
+    Resolved,
+}
+
+// Account to store a dispute raised against a milestone
+#[account]
+pub struct Dispute {
+    pub escrow: Pubkey,             // Associated escrow account
+    pub milestone: Pubkey,          // Disputed milestone
+    pub raised_by: Pubkey,          // Party that raised the dispute
+    pub raised_at: i64,             // Timestamp when the dispute was raised
+    pub status: DisputeStatus,      // Current dispute status
+    pub creator_evidence: Option<[u8; 32]>,   // Latest evidence hash from the creator
+    pub recipient_evidence: Option<[u8; 32]>, // Latest evidence hash from the recipient
+    pub recipient_share_bps: u16,   // Share of the milestone awarded to the recipient
+    pub resolved_at: Option<i64>,   // When the arbitrator resolved the dispute
+    pub bump: u8,                   // Bump seed for the dispute PDA
+}
+
 // Multisig account for emergency withdrawals
 #[account]
 pub struct Multisig {
     pub escrow: Pubkey,             // Associated escrow account
-    pub signers: Vec<Pubkey>,        // List of signers (3 total)
-    pub threshold: u8,              // Required signatures (2)
+    pub requester: Pubkey,          // Emergency signer who opened the request and paid its rent
+    pub signers: Vec<Pubkey>,       // Emergency signers copied from the escrow
+    pub threshold: u8,              // Required signatures
     pub signed_by: Vec<Pubkey>,     // Who has signed so far
     pub executed: bool,             // Whether the withdrawal was executed
+    pub objected: bool,             // Whether the recipient objected during the window
+    pub objection_upheld: Option<bool>, // Arbitrator's ruling on the objection; None until ruled
+    pub paused_escrow: bool,        // The objection moved the escrow into dispute
+    pub created_at: i64,            // Timestamp when the request was opened
+    pub approved_at: Option<i64>,   // Timestamp when the threshold was reached
     pub bump: u8,                   // Bump seed for the multisig PDA
 }
 
+impl Multisig {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.approved_at.is_none() && now >= self.created_at.saturating_add(EMERGENCY_REQUEST_TTL)
+    }
+    
+    pub fn executable_at(&self, delay: i64) -> Option<i64> {
+        self.approved_at.map(|approved_at| approved_at.saturating_add(delay))
+    }
+    
+    // An objection blocks the withdrawal until the arbitrator dismisses it
+    pub fn objection_pending(&self) -> bool {
+        self.objected && self.objection_upheld.is_none()
+    }
+}
+
+// Program-wide escrow settings
+#[account]
+pub struct Config {
+    pub admin: Pubkey,              // Authority allowed to update the config
+    pub fee_bps: u16,               // Protocol fee taken from each milestone release
+    pub treasury: Pubkey,           // Owner of the token accounts that collect fees
+    pub allowed_mints: Vec<Pubkey>, // Mints escrows may be created with (DAPPR_USD by default)
+    pub bump: u8,                   // Bump seed for the config PDA
+}
+
+impl Config {
+    pub fn fee_for(&self, amount: u64) -> Result<u64> {
+        (amount as u128 * self.fee_bps as u128 / MAX_BASIS_POINTS as u128)
+            .try_into()
+            .map_err(|_| error!(EscrowError::InvalidProtocolFee))
+    }
+}
+
+// Funds still held by the escrow; a native escrow's rent-exempt reserve is not counted
+fn escrow_balance<'info>(
+    escrow: &Account<'info, Escrow>,
+    escrow_token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+) -> Result<u64> {
+    if escrow.is_native {
+        let escrow_info = escrow.to_account_info();
+        let reserve = Rent::get()?.minimum_balance(escrow_info.data_len());
+        Ok(escrow_info.lamports().saturating_sub(reserve))
+    } else {
+        let escrow_token_account = escrow_token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingTokenAccount)?;
+        Ok(escrow_token_account.amount)
+    }
+}
+
+// Balance not yet set aside for funders, i.e. what releases, cancellations and withdrawals may pay out
+fn unallocated_balance<'info>(
+    escrow: &Account<'info, Escrow>,
+    escrow_token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+) -> Result<u64> {
+    Ok(escrow_balance(escrow, escrow_token_account)?.saturating_sub(escrow.unclaimed_refunds()))
+}
+
+// Whether `account` is rent-exempt once credited `amount` lamports; the runtime rejects native
+// payouts that would leave a wallet below that
+fn rent_exempt_after(account: &AccountInfo, amount: u64) -> Result<bool> {
+    let rent = Rent::get()?;
+    Ok(account.lamports()
+        .checked_add(amount)
+        .map_or(false, |total| rent.is_exempt(total, account.data_len())))
+}
+
+// Where a party is paid: the wallet itself for native escrows, its token account otherwise
+fn payout_account<'info>(
+    escrow: &Account<'info, Escrow>,
+    wallet: &AccountInfo<'info>,
+    token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+) -> Result<AccountInfo<'info>> {
+    if escrow.is_native {
+        Ok(wallet.clone())
+    } else {
+        let token_account = token_account
+            .as_ref()
+            .ok_or(EscrowError::MissingTokenAccount)?;
+        Ok(token_account.to_account_info())
+    }
+}
+
+// Moves funds out of the escrow, as lamports for native escrows or as a PDA-signed token transfer
+fn pay_from_escrow<'info>(
+    escrow: &Account<'info, Escrow>,
+    escrow_token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if escrow.is_native {
+        let from = escrow.to_account_info();
+        let rent = Rent::get()?;
+        
+        // The escrow keeps its reserve and the destination must not be left below rent exemption
+        require!(
+            from.lamports()
+                .checked_sub(amount)
+                .map_or(false, |left| left >= rent.minimum_balance(from.data_len())),
+            EscrowError::InsufficientFunds
+        );
+        require!(rent_exempt_after(&to, amount)?, EscrowError::PayoutBelowRentExemption);
+        
+        **from.try_borrow_mut_lamports()? -= amount;
+        **to.try_borrow_mut_lamports()? += amount;
+        return Ok(());
+    }
+    
+    let escrow_id = escrow.escrow_id.to_le_bytes();
+    let seeds = &[
+        ESCROW_PDA_SEED,
+        escrow.creator.as_ref(),
+        escrow.initial_recipient.as_ref(),
+        escrow_id.as_ref(),
+        &[escrow.bump],
+    ];
+    
+    let signer = &[&seeds[..]];
+    
+    let escrow_token_account = escrow_token_account
+        .as_ref()
+        .ok_or(EscrowError::MissingTokenAccount)?;
+    let cpi_accounts = TransferChecked {
+        from: escrow_token_account.to_account_info(),
+        mint: token_mint.to_account_info(),
+        to,
+        authority: escrow.to_account_info(),
+    };
+    
+    let cpi_program = token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token_interface::transfer_checked(cpi_ctx, amount, token_mint.decimals)
+}
+
+// Returns unreleased funds to the creator, unless others helped fund the escrow, in which case
+// they join the refund pool for every funder to claim their share
+fn refund_creator<'info>(
+    escrow: &mut Account<'info, Escrow>,
+    escrow_token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    creator: &AccountInfo<'info>,
+    creator_token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    
+    if escrow.crowdfunded {
+        escrow.refund_pool = escrow.refund_pool
+            .checked_add(amount)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+        
+        emit!(RefundPoolIncreased {
+            escrow: escrow.key(),
+            amount,
+            refund_pool: escrow.refund_pool,
+        });
+        return Ok(());
+    }
+    
+    let creator_account = payout_account(escrow, creator, creator_token_account)?;
+    pay_from_escrow(
+        escrow,
+        escrow_token_account,
+        token_mint,
+        token_program,
+        creator_account,
+        amount,
+    )?;
+    
+    escrow.refunded_amount = escrow.refunded_amount
+        .checked_add(amount)
+        .ok_or(EscrowError::InvalidMilestoneState)?;
+    Ok(())
+}
+
 // Events
 #[event]
 pub struct EscrowCreated {
@@ -57,6 +547,70 @@ pub struct EscrowCreated {
     pub amount: u64,
 }
 
+#[event]
+pub struct EscrowFunded {
+    pub escrow: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub funded_amount: u64,
+    pub committed_amount: u64,
+}
+
+#[event]
+pub struct MilestoneAdded {
+    pub escrow: Pubkey,
+    pub milestone: Pubkey,
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub deadline: i64,
+}
+
+#[event]
+pub struct MilestoneAmendmentProposed {
+    pub escrow: Pubkey,
+    pub milestone: Pubkey,
+    pub proposer: Pubkey,
+    pub amount: u64,
+    pub deadline: i64,
+}
+
+#[event]
+pub struct MilestoneAmended {
+    pub escrow: Pubkey,
+    pub milestone: Pubkey,
+    pub previous_amount: u64,
+    pub amount: u64,
+    pub previous_deadline: i64,
+    pub deadline: i64,
+    pub committed_amount: u64,
+}
+
+#[event]
+pub struct MilestoneSubmitted {
+    pub escrow: Pubkey,
+    pub milestone: Pubkey,
+    pub milestone_index: u8,
+    pub deliverable_hash: Vec<u8>,
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct MilestoneRejected {
+    pub escrow: Pubkey,
+    pub milestone: Pubkey,
+    pub verifier: Pubkey,
+    pub reason_code: u16,
+}
+
+#[event]
+pub struct MilestoneApproved {
+    pub escrow: Pubkey,
+    pub milestone: Pubkey,
+    pub verifier: Pubkey,
+    pub approvals: u8,
+    pub threshold: u8,
+}
+
 #[event]
 pub struct MilestoneCompleted {
     pub escrow: Pubkey,
@@ -70,7 +624,127 @@ pub struct FundsReleased {
     pub escrow: Pubkey,
     pub milestone: Pubkey,
     pub amount: u64,
+    pub fee: u64,
+    pub recipient: Pubkey,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct AllowedMintUpdated {
+    pub mint: Pubkey,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct PayeePaid {
+    pub escrow: Pubkey,
+    pub milestone: Pubkey,
+    pub payee: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MilestoneReclaimed {
+    pub escrow: Pubkey,
+    pub milestone: Pubkey,
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct VestedWithdrawn {
+    pub escrow: Pubkey,
     pub recipient: Pubkey,
+    pub amount: u64,
+    pub released_amount: u64,
+}
+
+#[event]
+pub struct VestingCancelled {
+    pub escrow: Pubkey,
+    pub recipient_amount: u64,
+    pub refund_pool: u64,
+}
+
+#[event]
+pub struct RecipientTransferred {
+    pub escrow: Pubkey,
+    pub previous_recipient: Pubkey,
+    pub new_recipient: Pubkey,
+}
+
+#[event]
+pub struct ReceiptMinted {
+    pub escrow: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub holder: Pubkey,
+}
+
+#[event]
+pub struct CancelProposed {
+    pub escrow: Pubkey,
+    pub proposer: Pubkey,
+    pub recipient_share_bps: u16,
+}
+
+#[event]
+pub struct EscrowCancelled {
+    pub escrow: Pubkey,
+    pub recipient_amount: u64,
+    pub refund_pool: u64,
+}
+
+#[event]
+pub struct RefundPoolIncreased {
+    pub escrow: Pubkey,
+    pub amount: u64,
+    pub refund_pool: u64,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub escrow: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub refunded_amount: u64,
+}
+
+#[event]
+pub struct EscrowClosed {
+    pub escrow: Pubkey,
+    pub creator: Pubkey,
+    pub milestones_closed: u8,
+}
+
+#[event]
+pub struct DisputeRaised {
+    pub escrow: Pubkey,
+    pub milestone: Pubkey,
+    pub dispute: Pubkey,
+    pub raised_by: Pubkey,
+}
+
+#[event]
+pub struct EvidenceSubmitted {
+    pub dispute: Pubkey,
+    pub submitted_by: Pubkey,
+    pub evidence_hash: [u8; 32],
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub escrow: Pubkey,
+    pub milestone: Pubkey,
+    pub dispute: Pubkey,
+    pub recipient_amount: u64,
+    pub creator_amount: u64,
 }
 
 #[event]
@@ -80,6 +754,40 @@ pub struct EmergencyWithdrawalRequested {
     pub amount: u64,
 }
 
+#[event]
+pub struct EmergencyWithdrawalCancelled {
+    pub escrow: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub expired: bool,
+}
+
+#[event]
+pub struct EmergencyWithdrawalApproved {
+    pub escrow: Pubkey,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawalObjected {
+    pub escrow: Pubkey,
+    pub recipient: Pubkey,
+}
+
+#[event]
+pub struct EmergencyObjectionResolved {
+    pub escrow: Pubkey,
+    pub arbitrator: Pubkey,
+    pub upheld: bool,
+}
+
+#[event]
+pub struct EmergencyWithdrawalExecuted {
+    pub escrow: Pubkey,
+    pub executor: Pubkey,
+    pub amount: u64,
+    pub recipient: Pubkey,
+}
+
 // Error codes
 #[error_code]
 pub enum EscrowError {
@@ -95,7 +803,7 @@ pub enum EscrowError {
     InvalidSigner,
     #[msg("Insufficient funds")]
     InsufficientFunds,
-    #[msg("Escrow not active")]
+    #[msg("Escrow is cancelled, withdrawn or completed and can no longer change status")]
     EscrowNotActive,
     #[msg("Invalid milestone state")]
     InvalidMilestoneState,
@@ -107,41 +815,247 @@ pub enum EscrowError {
     NotEnoughSignatures,
     #[msg("Already executed")]
     AlreadyExecuted,
+    #[msg("Only the creator or recipient can take part in a dispute")]
+    NotEscrowParty,
+    #[msg("Only the escrow arbitrator can resolve disputes")]
+    InvalidArbitrator,
+    #[msg("Milestone is under dispute")]
+    MilestoneDisputed,
+    #[msg("Dispute is not open")]
+    DisputeNotOpen,
+    #[msg("Invalid dispute share")]
+    InvalidDisputeShare,
+    #[msg("Signer is not an approved verifier for this escrow")]
+    UnauthorizedVerifier,
+    #[msg("Invalid verifier configuration")]
+    InvalidVerifierConfig,
+    #[msg("Verifier already approved this milestone")]
+    AlreadyApproved,
+    #[msg("Only the recipient can submit milestone deliverables")]
+    InvalidSubmitter,
+    #[msg("Invalid deliverable hash")]
+    InvalidDeliverableHash,
+    #[msg("Milestone already verified")]
+    MilestoneAlreadyVerified,
+    #[msg("Invalid review period")]
+    InvalidReviewPeriod,
+    #[msg("Review period has elapsed")]
+    ReviewPeriodElapsed,
+    #[msg("Invalid grace period")]
+    InvalidGracePeriod,
+    #[msg("Invalid cancellation share")]
+    InvalidCancelShare,
+    #[msg("No cancellation has been proposed")]
+    CancelNotProposed,
+    #[msg("Cancellation must be accepted by the other party")]
+    InvalidCancelAcceptor,
+    #[msg("Cancellation terms do not match the proposal")]
+    CancelTermsMismatch,
+    #[msg("Escrow is still active")]
+    EscrowStillActive,
+    #[msg("Escrow still holds funds")]
+    EscrowNotSettled,
+    #[msg("Account does not belong to this escrow")]
+    InvalidEscrowAccount,
+    #[msg("All milestones have already been added")]
+    MilestoneLimitReached,
+    #[msg("Milestones cannot be added once funding has started")]
+    FundingAlreadyStarted,
+    #[msg("Invalid milestone amount")]
+    InvalidMilestoneAmount,
+    #[msg("Invalid milestone deadline")]
+    InvalidDeadline,
+    #[msg("No milestones have been added")]
+    NoMilestones,
+    #[msg("Funding would exceed the committed milestone total")]
+    FundingExceedsCommitment,
+    #[msg("Escrow is not fully funded")]
+    EscrowNotFullyFunded,
+    #[msg("Escrow milestones are still being drafted")]
+    EscrowNotFinalized,
+    #[msg("Escrow is already in progress")]
+    EscrowInProgress,
+    #[msg("Escrow is under dispute")]
+    EscrowDisputed,
+    #[msg("Escrow has been cancelled")]
+    EscrowCancelled,
+    #[msg("Escrow funds were withdrawn through the emergency multisig")]
+    EscrowWithdrawn,
+    #[msg("Escrow is completed")]
+    EscrowCompleted,
+    #[msg("Emergency signers must be 1 to 10 distinct keys")]
+    InvalidEmergencySigners,
+    #[msg("Emergency withdrawal request has expired")]
+    EmergencyRequestExpired,
+    #[msg("Only the requester can cancel an emergency withdrawal before it expires")]
+    EmergencyRequestActive,
+    #[msg("Emergency withdrawal has not reached its signature threshold")]
+    EmergencyNotApproved,
+    #[msg("Emergency withdrawal is still in its objection window")]
+    EmergencyDelayActive,
+    #[msg("Objection window for this emergency withdrawal has closed")]
+    ObjectionWindowClosed,
+    #[msg("Payees must be 1 to 5 distinct wallets whose shares sum to 10,000 bps")]
+    InvalidPayees,
+    #[msg("Payee token accounts must match the escrow payees in order")]
+    InvalidPayeeAccount,
+    #[msg("Only the config admin can change program settings")]
+    UnauthorizedAdmin,
+    #[msg("Protocol fee cannot exceed 1,000 bps")]
+    InvalidProtocolFee,
+    #[msg("Token escrows require their token accounts")]
+    MissingTokenAccount,
+    #[msg("Native escrows must use the native mint and no token account")]
+    InvalidEscrowAsset,
+    #[msg("Payout would leave the destination below rent exemption")]
+    PayoutBelowRentExemption,
+    #[msg("Mint is not on the escrow allow-list")]
+    MintNotAllowed,
+    #[msg("Allow-list holds up to 10 distinct mints")]
+    InvalidAllowedMints,
+    #[msg("Vesting needs an amount and start <= cliff <= end with start < end")]
+    InvalidVestingSchedule,
+    #[msg("Escrow does not vest linearly")]
+    NotVestingEscrow,
+    #[msg("Nothing has vested since the last withdrawal")]
+    NothingVested,
+    #[msg("Funding deadline must be in the future")]
+    InvalidFundingDeadline,
+    #[msg("Funding deadline has passed")]
+    FundingDeadlinePassed,
+    #[msg("Refunds open once the escrow is cancelled or misses its funding deadline")]
+    RefundNotAvailable,
+    #[msg("New recipient must differ from the current one and not be the creator, a payee, verifier or arbitrator")]
+    InvalidNewRecipient,
+    #[msg("This escrow requires the creator to consent to recipient transfers")]
+    CreatorConsentRequired,
+    #[msg("A receipt can only be minted once for an open milestone escrow whose recipient is a payee")]
+    ReceiptUnavailable,
+    #[msg("Receipt token account must hold this escrow's receipt")]
+    InvalidReceiptAccount,
+    #[msg("No milestone amendment has been proposed")]
+    AmendmentNotProposed,
+    #[msg("Milestone amendment must be accepted by the other party")]
+    InvalidAmendmentAcceptor,
+    #[msg("Amendment terms do not match the proposal")]
+    AmendmentTermsMismatch,
+    #[msg("Every milestone and dispute of the escrow must be closed with it")]
+    CloseAccountsMismatch,
+    #[msg("Escrow cannot move from its current status to the requested one")]
+    InvalidStatusTransition,
+    #[msg("The recipient's objection to this emergency withdrawal stands")]
+    EmergencyRequestObjected,
+    #[msg("Recipient account must be the current holder of the recipient's rights")]
+    InvalidRightsHolder,
+    #[msg("Recipient rights are held through the receipt; transfer the receipt instead")]
+    ReceiptOutstanding,
+    #[msg("Invalid emergency withdrawal delay")]
+    InvalidEmergencyDelay,
+    #[msg("The arbitrator has not ruled on the recipient's objection yet")]
+    EmergencyObjectionPending,
+    #[msg("No objection to this emergency withdrawal is awaiting a ruling")]
+    NoPendingObjection,
+    #[msg("Deposits must transfer a positive amount")]
+    InvalidDepositAmount,
 }
 
 // Contexts
 #[derive(Accounts)]
-pub struct InitializeEscrow<'info> {
+pub struct InitializeConfig<'info> {
     #[account(mut)]
-    pub creator: Signer<'info>,
-    /// CHECK: The recipient of the funds
-    pub recipient: UncheckedAccount<'info>,
-    pub token_mint: Account<'info, token::Mint>,
+    pub admin: Signer<'info>,
     
     #[account(
         init,
-        payer = creator,
-        space = 8 + 32 + 32 + 32 + 8 + 8 + 1 + 1 + 8 + 1,
-        seeds = [ESCROW_PDA_SEED, creator.key.as_ref(), recipient.key.as_ref()],
+        payer = admin,
+        space = 8 + 32 + 2 + 32 + (4 + MAX_ALLOWED_MINTS * 32) + 1,
+        seeds = [CONFIG_PDA_SEED],
         bump
     )]
-    pub escrow: Account<'info, Escrow>,
+    pub config: Account<'info, Config>,
     
+    // First entry of the escrow mint allow-list; the DAPPR_USD mint lives at a PDA created by initialize_tokens
     #[account(
-        init,
-        payer = creator,
-        token::mint = token_mint,
-        token::authority = escrow,
+        seeds = [crate::tokens::DAPPR_USD_MINT_SEED],
+        bump,
     )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
+    pub dappr_usd_mint: InterfaceAccount<'info, Mint>,
     
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ EscrowError::UnauthorizedAdmin,
+    )]
+    pub program: Program<'info, crate::program::Dappr>,
+    
+    // Only the program's upgrade authority may create the config, so it cannot be claimed first by anyone else
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ EscrowError::UnauthorizedAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = admin @ EscrowError::UnauthorizedAdmin,
+        seeds = [CONFIG_PDA_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: InitializeEscrowParams)]
+pub struct InitializeEscrow<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: The recipient of the funds
+    pub recipient: UncheckedAccount<'info>,
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [CONFIG_PDA_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + 32 + 8 + 32 + 32 + 1 + (1 + 32) + (4 + MAX_PAYEES * (32 + 2)) + 32 + (4 + MAX_VERIFIERS * 32) + 1 + 8 + 8 + (4 + MAX_EMERGENCY_SIGNERS * 32) + 1 + 8 + 32 + 1 + 8 + 8 + 1 + (1 + 8) + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 1 + 1 + (1 + 8 + 8 + 8 + 8) + 1 + (1 + 32) + 2 + 8 + 1,
+        seeds = [
+            ESCROW_PDA_SEED,
+            creator.key.as_ref(),
+            recipient.key.as_ref(),
+            params.escrow_id.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(milestone_index: u8)]
 pub struct AddMilestone<'info> {
     #[account(mut)]
     pub creator: Signer<'info>,
@@ -155,8 +1069,9 @@ pub struct AddMilestone<'info> {
     #[account(
         init,
         payer = creator,
-        space = 8 + 32 + 1 + 8 + 8 + 1 + 1 + 1 + 32 + 1,
-        seeds = [MILESTONE_PDA_SEED, escrow.key().as_ref(), &[milestone_index]],
+        space = 8 + 32 + 1 + 8 + 8 + 1 + 1 + (4 + MAX_DELIVERABLE_HASH_LEN) + (1 + 8) + (1 + 2)
+            + (1 + 8) + (1 + 32) + (4 + MAX_VERIFIERS * 32) + 1 + (1 + 32 + 8 + 8) + 1,
+        seeds = [MILESTONE_PDA_SEED, escrow.key().as_ref(), &[escrow.milestones_added]],
         bump
     )]
     pub milestone: Account<'info, Milestone>,
@@ -176,28 +1091,166 @@ pub struct FundEscrow<'info> {
     )]
     pub escrow: Account<'info, Escrow>,
     
-    pub token_mint: Account<'info, token::Mint>,
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + 32 + 32 + 8 + 8 + 1,
+        seeds = [CONTRIBUTION_PDA_SEED, escrow.key().as_ref(), funder.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
         associated_token::mint = token_mint,
         associated_token::authority = funder,
+        associated_token::token_program = token_program,
     )]
-    pub funder_token_account: Account<'info, TokenAccount>,
+    pub funder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
         associated_token::mint = token_mint,
         associated_token::authority = escrow,
+        associated_token::token_program = token_program,
     )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CompleteMilestone<'info> {
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = token_mint,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+        has_one = funder,
+        seeds = [CONTRIBUTION_PDA_SEED, escrow.key().as_ref(), funder.key().as_ref()],
+        bump = contribution.bump,
+    )]
+    pub contribution: Account<'info, Contribution>,
+    
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = funder,
+        associated_token::token_program = token_program,
+    )]
+    pub funder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeMilestoneAmendment<'info> {
+    pub proposer: Signer<'info>,
+    
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub milestone: Account<'info, Milestone>,
+    
+    // Token account holding the escrow receipt, if one was minted
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptMilestoneAmendment<'info> {
+    pub acceptor: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = creator,
+        has_one = token_mint,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub milestone: Account<'info, Milestone>,
+    
+    // Token account holding the escrow receipt, if one was minted
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: The creator of the escrow, who is refunded deposits a decrease leaves uncommitted
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitMilestone<'info> {
+    pub recipient: Signer<'info>,
+    
+    #[account(
+        has_one = recipient @ EscrowError::InvalidSubmitter,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub milestone: Account<'info, Milestone>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveMilestone<'info> {
     pub verifier: Signer<'info>,
     
     #[account(mut)]
@@ -212,6 +1265,19 @@ pub struct CompleteMilestone<'info> {
     pub clock: Sysvar<'info, Clock>,
 }
 
+#[derive(Accounts)]
+pub struct RejectMilestone<'info> {
+    pub verifier: Signer<'info>,
+    
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub milestone: Account<'info, Milestone>,
+}
+
 #[derive(Accounts)]
 pub struct ReleaseFunds<'info> {
     #[account(mut)]
@@ -220,255 +1286,2181 @@ pub struct ReleaseFunds<'info> {
     #[account(
         mut,
         has_one = recipient,
+        has_one = token_mint,
     )]
     pub escrow: Account<'info, Escrow>,
     
     /// CHECK: The recipient of the funds
     pub recipient: UncheckedAccount<'info>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        has_one = escrow,
+        seeds = [MILESTONE_PDA_SEED, escrow.key().as_ref(), &[milestone.index]],
+        bump = milestone.bump,
+    )]
     pub milestone: Account<'info, Milestone>,
     
-    pub token_mint: Account<'info, token::Mint>,
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
         associated_token::mint = token_mint,
         associated_token::authority = escrow,
+        associated_token::token_program = token_program,
     )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        seeds = [CONFIG_PDA_SEED],
+        bump = config.bump,
+        has_one = treasury,
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// CHECK: The protocol treasury, matched against the config
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
     
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = recipient,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
     )]
-    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
-    pub token_program: Program<'info, Token>,
+    // Token account holding the escrow receipt, if one was minted
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
     pub clock: Sysvar<'info, Clock>,
+    
+    // Remaining accounts: each payee's associated token account (or wallet for native escrows), in `escrow.payees` order
 }
 
 #[derive(Accounts)]
-pub struct InitiateEmergencyWithdrawal<'info> {
-    pub requester: Signer<'info>,
+pub struct ReclaimExpiredMilestone<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
     
     #[account(
         mut,
         has_one = creator,
+        has_one = token_mint,
     )]
     pub escrow: Account<'info, Escrow>,
     
-    /// CHECK: The creator of the escrow
-    pub creator: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub milestone: Account<'info, Milestone>,
     
     #[account(
-        init_if_needed,
-        payer = requester,
-        space = 8 + 32 + 1 + 1 + 1 + (4 + 3 * 32) + (4 + 3 * 32) + 1 + 1,
-        seeds = [b"multisig", escrow.key().as_ref()],
-        bump
+        mint::token_program = token_program,
     )]
-    pub multisig: Account<'info, Multisig>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
     
-    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct SignEmergencyWithdrawal<'info> {
-    pub signer: Signer<'info>,
+pub struct WithdrawVested<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = recipient,
+        has_one = token_mint,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelVesting<'info> {
     #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = creator,
+        has_one = recipient,
+        has_one = token_mint,
+    )]
     pub escrow: Account<'info, Escrow>,
     
+    /// CHECK: The recipient of the vested funds
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
     #[account(
         mut,
-        has_one = escrow,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
     )]
-    pub multisig: Account<'info, Multisig>,
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct TransferRecipient<'info> {
+    pub recipient: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = recipient,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    // Only needed when the escrow requires creator consent
+    pub creator: Option<Signer<'info>>,
+    
+    /// CHECK: The wallet taking over the recipient's payment rights
+    pub new_recipient: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MintReceipt<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = recipient,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        init,
+        payer = recipient,
+        mint::decimals = 0,
+        mint::authority = escrow,
+        mint::freeze_authority = escrow,
+        seeds = [RECEIPT_MINT_SEED, escrow.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = recipient,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = recipient,
+    )]
+    pub receipt_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Created by the token metadata program; address checked in the handler
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+    
+    /// CHECK: Created by the token metadata program; address checked in the handler
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+    
+    /// CHECK: Matched against the token metadata program ID in the handler
+    pub token_metadata_program: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeCancel<'info> {
+    pub proposer: Signer<'info>,
+    
+    #[account(mut)]
+    pub escrow: Account<'info, Escrow>,
+    
+    // Token account holding the escrow receipt, if one was minted
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptCancel<'info> {
+    pub acceptor: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = token_mint,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    /// CHECK: Holder of the recipient's rights; checked against the receipt in the handler
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    // Token account holding the escrow receipt, if one was minted
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEscrow<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = creator,
+        has_one = token_mint,
+        close = creator,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    /// CHECK: Emergency request PDA, always passed so an open request is closed with the escrow;
+    /// it is only deserialized when the program owns it
+    #[account(
+        mut,
+        seeds = [b"multisig", escrow.key().as_ref()],
+        bump,
+    )]
+    pub multisig: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives the rent of the multisig; checked against `multisig.requester`
+    #[account(mut)]
+    pub requester: Option<UncheckedAccount<'info>>,
+    
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RaiseDispute<'info> {
+    #[account(mut)]
+    pub party: Signer<'info>,
+    
+    #[account(mut)]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub milestone: Account<'info, Milestone>,
+    
+    #[account(
+        init,
+        payer = party,
+        space = 8 + 32 + 32 + 32 + 8 + 1 + (1 + 32) + (1 + 32) + 2 + (1 + 8) + 1,
+        seeds = [DISPUTE_PDA_SEED, milestone.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitEvidence<'info> {
+    pub party: Signer<'info>,
+    
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub dispute: Account<'info, Dispute>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    pub arbitrator: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = arbitrator @ EscrowError::InvalidArbitrator,
+        has_one = creator,
+        has_one = token_mint,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub milestone: Account<'info, Milestone>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+        has_one = milestone,
+    )]
+    pub dispute: Account<'info, Dispute>,
+    
+    /// CHECK: The creator of the escrow
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    
+    /// CHECK: Holder of the recipient's rights; checked against the receipt in the handler
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    // Token account holding the escrow receipt, if one was minted
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitiateEmergencyWithdrawal<'info> {
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = creator,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    /// CHECK: The creator of the escrow
+    pub creator: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + 32 + 32 + (4 + escrow.emergency_signers.len() * 32) + 1 + (4 + escrow.emergency_signers.len() * 32) + 1 + 1 + (1 + 1) + 1 + 8 + (1 + 8) + 1,
+        seeds = [b"multisig", escrow.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelEmergencyWithdrawal<'info> {
+    pub canceller: Signer<'info>,
+    
+    #[account(mut)]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+        has_one = requester,
+        close = requester,
+    )]
+    pub multisig: Account<'info, Multisig>,
+    
+    /// CHECK: Receives the rent of the closed request
+    #[account(mut)]
+    pub requester: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SignEmergencyWithdrawal<'info> {
+    pub signer: Signer<'info>,
+    
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub multisig: Account<'info, Multisig>,
+}
+
+#[derive(Accounts)]
+pub struct ObjectEmergencyWithdrawal<'info> {
+    pub recipient: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = recipient @ EscrowError::NotEscrowParty,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub multisig: Account<'info, Multisig>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveEmergencyObjection<'info> {
+    pub arbitrator: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = arbitrator @ EscrowError::InvalidArbitrator,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub multisig: Account<'info, Multisig>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteEmergencyWithdrawal<'info> {
+    pub executor: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = creator,
+        has_one = token_mint,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    
+    #[account(
+        mut,
+        has_one = escrow,
+    )]
+    pub multisig: Account<'info, Multisig>,
+    
+    /// CHECK: The creator of the escrow, who receives the withdrawn funds
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Implementation of the escrow program
+impl<'info> InitializeConfig<'info> {
+    pub fn initialize(&mut self, bump: u8, fee_bps: u16, treasury: Pubkey) -> Result<()> {
+        require!(fee_bps <= MAX_PROTOCOL_FEE_BPS, EscrowError::InvalidProtocolFee);
+        
+        self.config.admin = self.admin.key();
+        self.config.fee_bps = fee_bps;
+        self.config.treasury = treasury;
+        self.config.allowed_mints = vec![self.dappr_usd_mint.key()];
+        self.config.bump = bump;
+        
+        emit!(ConfigUpdated {
+            admin: self.admin.key(),
+            fee_bps,
+            treasury,
+        });
+        
+        emit!(AllowedMintUpdated {
+            mint: self.dappr_usd_mint.key(),
+            allowed: true,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update(&mut self, fee_bps: u16, treasury: Pubkey) -> Result<()> {
+        require!(fee_bps <= MAX_PROTOCOL_FEE_BPS, EscrowError::InvalidProtocolFee);
+        
+        self.config.fee_bps = fee_bps;
+        self.config.treasury = treasury;
+        
+        emit!(ConfigUpdated {
+            admin: self.admin.key(),
+            fee_bps,
+            treasury,
+        });
+        
+        Ok(())
+    }
+    
+    pub fn add_allowed_mint(&mut self, mint: Pubkey) -> Result<()> {
+        require!(
+            self.config.allowed_mints.len() < MAX_ALLOWED_MINTS
+                && !self.config.allowed_mints.contains(&mint),
+            EscrowError::InvalidAllowedMints
+        );
+        
+        self.config.allowed_mints.push(mint);
+        
+        emit!(AllowedMintUpdated {
+            mint,
+            allowed: true,
+        });
+        
+        Ok(())
+    }
+    
+    // Existing escrows keep their mint; only new escrows are affected
+    pub fn remove_allowed_mint(&mut self, mint: Pubkey) -> Result<()> {
+        let index = self.config.allowed_mints
+            .iter()
+            .position(|allowed| *allowed == mint)
+            .ok_or(EscrowError::MintNotAllowed)?;
+        
+        self.config.allowed_mints.remove(index);
+        
+        emit!(AllowedMintUpdated {
+            mint,
+            allowed: false,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> InitializeEscrow<'info> {
+    // Native escrows pass each payee wallet, in payee order, as a remaining account
+    pub fn initialize(&mut self, params: InitializeEscrowParams, payee_wallets: &[AccountInfo]) -> Result<()> {
+        let InitializeEscrowParams {
+            escrow_id,
+            bump,
+            milestones_count,
+            arbitrator,
+            verifiers,
+            verifier_threshold,
+            review_period,
+            grace_period,
+            emergency_signers,
+            emergency_threshold,
+            emergency_delay,
+            payees,
+            is_native,
+            vesting,
+            funding_deadline,
+            transfer_requires_creator,
+        } = params;
+        
+        require!(
+            self.config.allowed_mints.contains(&self.token_mint.key()),
+            EscrowError::MintNotAllowed
+        );
+        
+        // A vesting escrow unlocks its funds over time instead of through milestones
+        if let Some(schedule) = &vesting {
+            require!(milestones_count == 0, EscrowError::InvalidMilestoneIndex);
+            require!(
+                schedule.amount > 0
+                    && schedule.start_ts < schedule.end_ts
+                    && schedule.start_ts <= schedule.cliff_ts
+                    && schedule.cliff_ts <= schedule.end_ts,
+                EscrowError::InvalidVestingSchedule
+            );
+        } else {
+            require!(milestones_count > 0, EscrowError::InvalidMilestoneIndex);
+        }
+        require!(review_period > 0, EscrowError::InvalidReviewPeriod);
+        require!(grace_period >= 0, EscrowError::InvalidGracePeriod);
+        require!(emergency_delay > 0, EscrowError::InvalidEmergencyDelay);
+        require!(
+            arbitrator != self.creator.key() && arbitrator != self.recipient.key(),
+            EscrowError::InvalidArbitrator
+        );
+        
+        // Without a reviewer list the creator alone approves milestones
+        let (verifiers, verifier_threshold) = if verifiers.is_empty() {
+            (vec![self.creator.key()], 1)
+        } else {
+            (verifiers, verifier_threshold)
+        };
+        
+        require!(
+            verifiers.len() <= MAX_VERIFIERS
+                && verifier_threshold > 0
+                && verifier_threshold as usize <= verifiers.len(),
+            EscrowError::InvalidVerifierConfig
+        );
+        require!(
+            !verifiers.contains(&self.recipient.key()),
+            EscrowError::InvalidVerifierConfig
+        );
+        for (i, verifier) in verifiers.iter().enumerate() {
+            require!(
+                !verifiers[..i].contains(verifier),
+                EscrowError::InvalidVerifierConfig
+            );
+        }
+        
+        require!(
+            !emergency_signers.is_empty() && emergency_signers.len() <= MAX_EMERGENCY_SIGNERS,
+            EscrowError::InvalidEmergencySigners
+        );
+        require!(
+            emergency_threshold > 0 && emergency_threshold as usize <= emergency_signers.len(),
+            EscrowError::InvalidMultisigThreshold
+        );
+        for (i, signer) in emergency_signers.iter().enumerate() {
+            require!(
+                !emergency_signers[..i].contains(signer),
+                EscrowError::InvalidEmergencySigners
+            );
+        }
+        
+        // Without a payee list the recipient receives every release in full
+        let payees = if payees.is_empty() {
+            vec![Payee {
+                wallet: self.recipient.key(),
+                share_bps: MAX_BASIS_POINTS,
+            }]
+        } else {
+            payees
+        };
+        
+        require!(payees.len() <= MAX_PAYEES, EscrowError::InvalidPayees);
+        let mut total_bps: u32 = 0;
+        for (i, payee) in payees.iter().enumerate() {
+            require!(payee.share_bps > 0, EscrowError::InvalidPayees);
+            require!(
+                !payees[..i].iter().any(|other| other.wallet == payee.wallet),
+                EscrowError::InvalidPayees
+            );
+            total_bps += payee.share_bps as u32;
+        }
+        require!(total_bps == MAX_BASIS_POINTS as u32, EscrowError::InvalidPayees);
+        
+        // Native escrows record the native mint and keep their lamports in the escrow PDA
+        if is_native {
+            require!(
+                self.token_mint.key() == native_mint::ID && self.escrow_token_account.is_none(),
+                EscrowError::InvalidEscrowAsset
+            );
+            
+            // Releases pay lamports straight into the payee wallets, so each must already be rent-exempt;
+            // otherwise a share too small to fund an empty wallet would block every release
+            require!(payee_wallets.len() == payees.len(), EscrowError::InvalidPayeeAccount);
+            for (payee, wallet) in payees.iter().zip(payee_wallets) {
+                require_keys_eq!(wallet.key(), payee.wallet, EscrowError::InvalidPayeeAccount);
+                require!(rent_exempt_after(wallet, 0)?, EscrowError::PayoutBelowRentExemption);
+            }
+        } else {
+            require!(self.escrow_token_account.is_some(), EscrowError::MissingTokenAccount);
+        }
+        
+        let clock = Clock::get()?;
+        if let Some(deadline) = funding_deadline {
+            require!(deadline > clock.unix_timestamp, EscrowError::InvalidFundingDeadline);
+        }
+        
+        self.escrow.creator = self.creator.key();
+        self.escrow.escrow_id = escrow_id;
+        self.escrow.recipient = self.recipient.key();
+        self.escrow.initial_recipient = self.recipient.key();
+        self.escrow.transfer_requires_creator = transfer_requires_creator;
+        self.escrow.receipt_mint = None;
+        self.escrow.payees = payees;
+        self.escrow.arbitrator = arbitrator;
+        self.escrow.verifiers = verifiers;
+        self.escrow.verifier_threshold = verifier_threshold;
+        self.escrow.review_period = review_period;
+        self.escrow.grace_period = grace_period;
+        self.escrow.emergency_signers = emergency_signers;
+        self.escrow.emergency_threshold = emergency_threshold;
+        self.escrow.emergency_delay = emergency_delay;
+        self.escrow.token_mint = self.token_mint.key();
+        self.escrow.is_native = is_native;
+        self.escrow.funded_amount = 0;
+        self.escrow.amendment_refunds = 0;
+        self.escrow.funding_status = FundingStatus::Unfunded;
+        self.escrow.funding_deadline = funding_deadline;
+        self.escrow.crowdfunded = false;
+        self.escrow.released_amount = 0;
+        self.escrow.refunded_amount = 0;
+        self.escrow.refund_pool = 0;
+        self.escrow.refunds_claimed = 0;
+        self.escrow.refunded_contributions = 0;
+        self.escrow.milestones_count = milestones_count;
+        self.escrow.milestones_added = 0;
+        self.escrow.committed_amount = vesting.map_or(0, |schedule| schedule.amount);
+        self.escrow.current_milestone = 0;
+        self.escrow.disputes_raised = 0;
+        self.escrow.vesting = vesting;
+        self.escrow.status = EscrowStatus::Draft;
+        self.escrow.cancel_proposed_by = None;
+        self.escrow.cancel_recipient_share_bps = 0;
+        self.escrow.created_at = clock.unix_timestamp;
+        self.escrow.bump = bump;
+        
+        // The vesting schedule is the whole commitment, so there is nothing left to draft
+        if vesting.is_some() {
+            self.escrow.transition(EscrowStatus::AwaitingFunding)?;
+        }
+        
+        emit!(EscrowCreated {
+            escrow: self.escrow.key(),
+            creator: self.creator.key(),
+            recipient: self.recipient.key(),
+            amount: 0,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> AddMilestone<'info> {
+    pub fn add_milestone(
+        &mut self,
+        bump: u8,
+        amount: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        require!(
+            self.escrow.funding_status == FundingStatus::Unfunded,
+            EscrowError::FundingAlreadyStarted
+        );
+        require!(
+            self.escrow.milestones_added < self.escrow.milestones_count,
+            EscrowError::MilestoneLimitReached
+        );
+        self.escrow.require_status(&[EscrowStatus::Draft])?;
+        require!(amount > 0, EscrowError::InvalidMilestoneAmount);
+        
+        let clock = Clock::get()?;
+        require!(deadline > clock.unix_timestamp, EscrowError::InvalidDeadline);
+        
+        // Milestones are indexed in the order they are added, matching their PDA seed
+        let index = self.escrow.milestones_added;
+        
+        self.milestone.escrow = self.escrow.key();
+        self.milestone.index = index;
+        self.milestone.amount = amount;
+        self.milestone.deadline = deadline;
+        self.milestone.completed = false;
+        self.milestone.verified = false;
+        self.milestone.deliverable_hash = Vec::new();
+        self.milestone.submitted_at = None;
+        self.milestone.rejection_code = None;
+        self.milestone.verified_at = None;
+        self.milestone.verified_by = None;
+        self.milestone.approvals = Vec::new();
+        self.milestone.disputed = false;
+        self.milestone.pending_amendment = None;
+        self.milestone.bump = bump;
+        
+        self.escrow.milestones_added = index
+            .checked_add(1)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+            
+        self.escrow.committed_amount = self.escrow.committed_amount.checked_add(amount)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+            
+        if self.escrow.milestones_added == self.escrow.milestones_count {
+            self.escrow.transition(EscrowStatus::AwaitingFunding)?;
+        }
+        
+        emit!(MilestoneAdded {
+            escrow: self.escrow.key(),
+            milestone: self.milestone.key(),
+            milestone_index: index,
+            amount,
+            deadline,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> ProposeMilestoneAmendment<'info> {
+    pub fn propose(&mut self, amount: u64, deadline: i64) -> Result<()> {
+        self.escrow.require_status(&[
+            EscrowStatus::Draft,
+            EscrowStatus::AwaitingFunding,
+            EscrowStatus::InProgress,
+        ])?;
+        require!(
+            self.proposer.key() == self.escrow.creator
+                || self.proposer.key() == self.escrow.rights_holder(&self.receipt_token_account)?,
+            EscrowError::NotEscrowParty
+        );
+        require!(
+            self.milestone.index >= self.escrow.current_milestone,
+            EscrowError::InvalidMilestoneIndex
+        );
+        require!(!self.milestone.disputed, EscrowError::MilestoneDisputed);
+        require!(amount > 0, EscrowError::InvalidMilestoneAmount);
+        require!(deadline > Clock::get()?.unix_timestamp, EscrowError::InvalidDeadline);
+        
+        // A new proposal from either party replaces any pending one
+        self.milestone.pending_amendment = Some(MilestoneAmendment {
+            proposer: self.proposer.key(),
+            amount,
+            deadline,
+        });
+        
+        emit!(MilestoneAmendmentProposed {
+            escrow: self.escrow.key(),
+            milestone: self.milestone.key(),
+            proposer: self.proposer.key(),
+            amount,
+            deadline,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> AcceptMilestoneAmendment<'info> {
+    pub fn accept(&mut self, amount: u64, deadline: i64) -> Result<()> {
+        self.escrow.require_status(&[
+            EscrowStatus::Draft,
+            EscrowStatus::AwaitingFunding,
+            EscrowStatus::InProgress,
+        ])?;
+        
+        // A proposal from a former holder of the recipient's rights no longer stands
+        let rights_holder = self.escrow.rights_holder(&self.receipt_token_account)?;
+        let amendment = self.milestone.pending_amendment
+            .filter(|amendment| {
+                amendment.proposer == self.escrow.creator || amendment.proposer == rights_holder
+            })
+            .ok_or(EscrowError::AmendmentNotProposed)?;
+        let counterparty = if amendment.proposer == self.escrow.creator {
+            rights_holder
+        } else {
+            self.escrow.creator
+        };
+        require!(
+            self.acceptor.key() == counterparty,
+            EscrowError::InvalidAmendmentAcceptor
+        );
+        
+        // Guard against the proposal being swapped out before acceptance lands
+        require!(
+            amount == amendment.amount && deadline == amendment.deadline,
+            EscrowError::AmendmentTermsMismatch
+        );
+        require!(
+            self.milestone.index >= self.escrow.current_milestone,
+            EscrowError::InvalidMilestoneIndex
+        );
+        require!(!self.milestone.disputed, EscrowError::MilestoneDisputed);
+        require!(deadline > Clock::get()?.unix_timestamp, EscrowError::InvalidDeadline);
+        
+        let committed_amount = self.escrow.committed_amount
+            .checked_sub(self.milestone.amount)
+            .and_then(|total| total.checked_add(amount))
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+        
+        // Deposits a decrease leaves uncommitted are handed back rather than stranded in the escrow
+        let excess = self.escrow.covered_amount().saturating_sub(committed_amount);
+        if excess > 0 {
+            refund_creator(
+                &mut self.escrow,
+                &self.escrow_token_account,
+                &self.token_mint,
+                &self.token_program,
+                &self.creator.to_account_info(),
+                &self.creator_token_account,
+                excess,
+            )?;
+            self.escrow.amendment_refunds = self.escrow.amendment_refunds
+                .checked_add(excess)
+                .ok_or(EscrowError::InvalidMilestoneState)?;
+        }
+        
+        let previous_amount = self.milestone.amount;
+        let previous_deadline = self.milestone.deadline;
+        self.milestone.amount = amount;
+        self.milestone.deadline = deadline;
+        self.milestone.pending_amendment = None;
+        self.escrow.committed_amount = committed_amount;
+        
+        // Once funding has started, the escrow only runs while its deposits cover every milestone
+        if self.escrow.status != EscrowStatus::Draft {
+            if self.escrow.covered_amount() == committed_amount {
+                self.escrow.funding_status = FundingStatus::FullyFunded;
+                self.escrow.funding_deadline = None;
+                if self.escrow.status == EscrowStatus::AwaitingFunding {
+                    self.escrow.transition(EscrowStatus::InProgress)?;
+                }
+            } else {
+                self.escrow.funding_status = if self.escrow.covered_amount() == 0 {
+                    FundingStatus::Unfunded
+                } else {
+                    FundingStatus::PartiallyFunded
+                };
+                if self.escrow.status == EscrowStatus::InProgress {
+                    self.escrow.transition(EscrowStatus::AwaitingFunding)?;
+                }
+            }
+        }
+        
+        emit!(MilestoneAmended {
+            escrow: self.escrow.key(),
+            milestone: self.milestone.key(),
+            previous_amount,
+            amount,
+            previous_deadline,
+            deadline,
+            committed_amount,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> FundEscrow<'info> {
+    pub fn fund(&mut self, amount: u64, bump: u8) -> Result<()> {
+        require!(amount > 0, EscrowError::InvalidDepositAmount);
+        self.escrow.require_status(&[EscrowStatus::Draft, EscrowStatus::AwaitingFunding])?;
+        // Only the creator's first deposit may close milestone registration on a draft
+        if self.escrow.status == EscrowStatus::Draft {
+            require_keys_eq!(
+                self.funder.key(),
+                self.escrow.creator,
+                EscrowError::EscrowNotFinalized
+            );
+        }
+        require!(
+            self.escrow.milestones_added > 0 || self.escrow.vesting.is_some(),
+            EscrowError::NoMilestones
+        );
+        if let Some(deadline) = self.escrow.funding_deadline {
+            require!(
+                Clock::get()?.unix_timestamp <= deadline,
+                EscrowError::FundingDeadlinePassed
+            );
+        }
+        
+        let received = if self.escrow.is_native {
+            // Transfer lamports from funder to the escrow PDA
+            let cpi_accounts = system_program::Transfer {
+                from: self.funder.to_account_info(),
+                to: self.escrow.to_account_info(),
+            };
+            
+            let cpi_program = self.system_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            system_program::transfer(cpi_ctx, amount)?;
+            
+            amount
+        } else {
+            // Transfer tokens from funder to escrow
+            let funder_token_account = self.funder_token_account
+                .as_ref()
+                .ok_or(EscrowError::MissingTokenAccount)?;
+            let escrow_token_account = self.escrow_token_account
+                .as_mut()
+                .ok_or(EscrowError::MissingTokenAccount)?;
+            let balance_before = escrow_token_account.amount;
+            
+            let cpi_accounts = TransferChecked {
+                from: funder_token_account.to_account_info(),
+                mint: self.token_mint.to_account_info(),
+                to: escrow_token_account.to_account_info(),
+                authority: self.funder.to_account_info(),
+            };
+            
+            let cpi_program = self.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            token_interface::transfer_checked(cpi_ctx, amount, self.token_mint.decimals)?;
+            
+            // Transfer-fee mints withhold part of the deposit, so only count what arrived
+            escrow_token_account.reload()?;
+            escrow_token_account.amount
+                .checked_sub(balance_before)
+                .ok_or(EscrowError::InsufficientFunds)?
+        };
+        // A deposit the transfer fee swallowed whole would record a contribution with no stake
+        require!(received > 0, EscrowError::InvalidDepositAmount);
+        
+        let funded_amount = self.escrow.funded_amount.checked_add(received)
+            .ok_or(EscrowError::FundingExceedsCommitment)?;
+        let covered_amount = funded_amount.saturating_sub(self.escrow.amendment_refunds);
+        require!(
+            covered_amount <= self.escrow.committed_amount,
+            EscrowError::FundingExceedsCommitment
+        );
+        
+        // A funder's first deposit creates their contribution record
+        if self.contribution.escrow == Pubkey::default() {
+            self.contribution.escrow = self.escrow.key();
+            self.contribution.funder = self.funder.key();
+            self.contribution.amount = 0;
+            self.contribution.refunded = 0;
+            self.contribution.bump = bump;
+        }
+        if self.funder.key() != self.escrow.creator {
+            self.escrow.crowdfunded = true;
+        }
+        self.contribution.amount = self.contribution.amount.checked_add(received)
+            .ok_or(EscrowError::FundingExceedsCommitment)?;
+        
+        // Funding closes milestone registration, so the schedule is whatever was added
+        self.escrow.milestones_count = self.escrow.milestones_added;
+        self.escrow.funded_amount = funded_amount;
+        if covered_amount == self.escrow.committed_amount {
+            // The goal is met, so a later amendment reopening funding is not cut short by the deadline
+            self.escrow.funding_status = FundingStatus::FullyFunded;
+            self.escrow.funding_deadline = None;
+            self.escrow.transition(EscrowStatus::InProgress)?;
+        } else {
+            self.escrow.funding_status = FundingStatus::PartiallyFunded;
+            if self.escrow.status == EscrowStatus::Draft {
+                self.escrow.transition(EscrowStatus::AwaitingFunding)?;
+            }
+        }
+        
+        emit!(EscrowFunded {
+            escrow: self.escrow.key(),
+            funder: self.funder.key(),
+            amount: received,
+            funded_amount,
+            committed_amount: self.escrow.committed_amount,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> ClaimRefund<'info> {
+    pub fn claim(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let missed_deadline = self.escrow.funding_deadline
+            .map_or(false, |deadline| now > deadline);
+        
+        // An escrow that missed its funding goal is cancelled by the first claim and refunds everything
+        if missed_deadline
+            && matches!(self.escrow.status, EscrowStatus::Draft | EscrowStatus::AwaitingFunding)
+        {
+            let unallocated = unallocated_balance(&self.escrow, &self.escrow_token_account)?;
+            self.escrow.refund_pool = self.escrow.refund_pool
+                .checked_add(unallocated)
+                .ok_or(EscrowError::InvalidMilestoneState)?;
+            self.escrow.transition(EscrowStatus::Cancelled)?;
+        }
+        
+        // The pool can grow while the escrow runs, so funders claim what it has added since their last
+        // claim; once the escrow is finished their contribution is settled and closed
+        let settling = !self.escrow.is_active();
+        let refunded_contributions = if settling {
+            self.escrow.refunded_contributions
+                .checked_add(self.contribution.amount)
+                .ok_or(EscrowError::InvalidMilestoneState)?
+        } else {
+            self.escrow.refunded_contributions
+        };
+        
+        // Each funder gets their share of the pool; the last one to settle also takes the rounding dust
+        let amount = if settling && refunded_contributions == self.escrow.funded_amount {
+            escrow_balance(&self.escrow, &self.escrow_token_account)?
+        } else {
+            let share: u64 = (self.contribution.amount as u128 * self.escrow.refund_pool as u128)
+                .checked_div(self.escrow.funded_amount as u128)
+                .unwrap_or(0)
+                .try_into()
+                .map_err(|_| error!(EscrowError::InvalidMilestoneState))?;
+            share.saturating_sub(self.contribution.refunded)
+        };
+        require!(settling || amount > 0, EscrowError::RefundNotAvailable);
+        
+        if amount > 0 {
+            let funder_account = payout_account(
+                &self.escrow,
+                &self.funder.to_account_info(),
+                &self.funder_token_account,
+            )?;
+            pay_from_escrow(
+                &self.escrow,
+                &self.escrow_token_account,
+                &self.token_mint,
+                &self.token_program,
+                funder_account,
+                amount,
+            )?;
+        }
+        
+        self.contribution.refunded = self.contribution.refunded
+            .checked_add(amount)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+        self.escrow.refunds_claimed = self.escrow.refunds_claimed
+            .checked_add(amount)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+        self.escrow.refunded_contributions = refunded_contributions;
+        self.escrow.refunded_amount = self.escrow.refunded_amount
+            .checked_add(amount)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+        
+        emit!(RefundClaimed {
+            escrow: self.escrow.key(),
+            funder: self.funder.key(),
+            amount,
+            refunded_amount: self.escrow.refunded_amount,
+        });
+        
+        if settling {
+            self.contribution.close(self.funder.to_account_info())?;
+        }
+        
+        Ok(())
+    }
+}
+
+impl<'info> SubmitMilestone<'info> {
+    pub fn submit(&mut self, deliverable_hash: Vec<u8>) -> Result<()> {
+        self.escrow.require_status(&[EscrowStatus::InProgress])?;
+        require!(
+            self.milestone.index == self.escrow.current_milestone,
+            EscrowError::InvalidMilestoneIndex
+        );
+        require!(!self.milestone.completed, EscrowError::MilestoneAlreadyCompleted);
+        require!(!self.milestone.disputed, EscrowError::MilestoneDisputed);
+        require!(
+            !deliverable_hash.is_empty() && deliverable_hash.len() <= MAX_DELIVERABLE_HASH_LEN,
+            EscrowError::InvalidDeliverableHash
+        );
+        
+        let clock = Clock::get()?;
+        
+        self.milestone.completed = true;
+        self.milestone.deliverable_hash = deliverable_hash.clone();
+        self.milestone.submitted_at = Some(clock.unix_timestamp);
+        self.milestone.rejection_code = None;
+        self.milestone.approvals = Vec::new();
+        
+        emit!(MilestoneSubmitted {
+            escrow: self.escrow.key(),
+            milestone: self.milestone.key(),
+            milestone_index: self.milestone.index,
+            deliverable_hash,
+            submitted_at: clock.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> ApproveMilestone<'info> {
+    pub fn approve(&mut self) -> Result<()> {
+        self.escrow.require_status(&[EscrowStatus::InProgress])?;
+        require!(
+            self.milestone.index == self.escrow.current_milestone,
+            EscrowError::InvalidMilestoneIndex
+        );
+        require!(self.milestone.completed, EscrowError::MilestoneNotCompleted);
+        require!(!self.milestone.verified, EscrowError::MilestoneAlreadyVerified);
+        require!(!self.milestone.disputed, EscrowError::MilestoneDisputed);
+        require!(
+            self.escrow.verifiers.contains(&self.verifier.key()),
+            EscrowError::UnauthorizedVerifier
+        );
+        require!(
+            !self.milestone.approvals.contains(&self.verifier.key()),
+            EscrowError::AlreadyApproved
+        );
+        
+        let clock = Clock::get()?;
+        
+        self.milestone.approvals.push(self.verifier.key());
+        
+        emit!(MilestoneApproved {
+            escrow: self.escrow.key(),
+            milestone: self.milestone.key(),
+            verifier: self.verifier.key(),
+            approvals: self.milestone.approvals.len() as u8,
+            threshold: self.escrow.verifier_threshold,
+        });
+        
+        // Accept the submission once enough verifiers have approved
+        if (self.milestone.approvals.len() as u8) >= self.escrow.verifier_threshold {
+            self.milestone.verified = true;
+            self.milestone.verified_at = Some(clock.unix_timestamp);
+            self.milestone.verified_by = Some(self.verifier.key());
+            
+            emit!(MilestoneCompleted {
+                escrow: self.escrow.key(),
+                milestone: self.milestone.key(),
+                milestone_index: self.milestone.index,
+                amount: self.milestone.amount,
+            });
+        }
+        
+        Ok(())
+    }
+}
+
+impl<'info> RejectMilestone<'info> {
+    pub fn reject(&mut self, reason_code: u16) -> Result<()> {
+        self.escrow.require_status(&[EscrowStatus::InProgress])?;
+        require!(
+            self.milestone.index == self.escrow.current_milestone,
+            EscrowError::InvalidMilestoneIndex
+        );
+        require!(self.milestone.completed, EscrowError::MilestoneNotCompleted);
+        require!(!self.milestone.verified, EscrowError::MilestoneAlreadyVerified);
+        require!(!self.milestone.disputed, EscrowError::MilestoneDisputed);
+        require!(
+            self.escrow.verifiers.contains(&self.verifier.key()),
+            EscrowError::UnauthorizedVerifier
+        );
+        
+        let clock = Clock::get()?;
+        
+        require!(
+            !self.milestone.review_period_elapsed(self.escrow.review_period, clock.unix_timestamp),
+            EscrowError::ReviewPeriodElapsed
+        );
+        
+        // Send the milestone back to the recipient for resubmission
+        self.milestone.completed = false;
+        self.milestone.deliverable_hash = Vec::new();
+        self.milestone.submitted_at = None;
+        self.milestone.rejection_code = Some(reason_code);
+        self.milestone.approvals = Vec::new();
+        
+        emit!(MilestoneRejected {
+            escrow: self.escrow.key(),
+            milestone: self.milestone.key(),
+            verifier: self.verifier.key(),
+            reason_code,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> ReleaseFunds<'info> {
+    pub fn release(&mut self, payee_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        self.escrow.require_status(&[EscrowStatus::InProgress])?;
+        require!(
+            self.milestone.index == self.escrow.current_milestone,
+            EscrowError::InvalidMilestoneIndex
+        );
+        require!(self.milestone.completed, EscrowError::MilestoneNotCompleted);
+        require!(!self.milestone.disputed, EscrowError::MilestoneDisputed);
+        
+        let clock = Clock::get()?;
+        
+        // An unverified submission is only payable once the review period has lapsed
+        if !self.milestone.verified {
+            require!(
+                self.milestone.review_period_elapsed(self.escrow.review_period, clock.unix_timestamp),
+                EscrowError::MilestoneNotCompleted
+            );
+            
+            self.milestone.verified = true;
+            self.milestone.verified_at = Some(clock.unix_timestamp);
+            self.milestone.verified_by = None;
+        }
+        
+        // Transfer funds from escrow to the payees
+        let transfer_amount = self.milestone.amount;
+        
+        // Verify escrow has sufficient balance outside the funders' unclaimed refunds
+        require!(
+            unallocated_balance(&self.escrow, &self.escrow_token_account)? >= transfer_amount,
+            EscrowError::InsufficientFunds
+        );
+        require!(
+            payee_accounts.len() == self.escrow.payees.len(),
+            EscrowError::InvalidPayeeAccount
+        );
+        
+        // Once a receipt is minted, the recipient's share goes to whoever holds it
+        let rights_holder = self.escrow.rights_holder(&self.receipt_token_account)?;
+        
+        // The protocol fee comes off the top before the payees are paid; a native fee too small to
+        // leave the treasury wallet rent-exempt is waived rather than blocking the release
+        let mut fee = self.config.fee_for(transfer_amount)?;
+        if self.escrow.is_native && !rent_exempt_after(&self.treasury.to_account_info(), fee)? {
+            fee = 0;
+        }
+        let shares = self.escrow.split_among_payees(transfer_amount - fee)?;
+        
+        // Perform the transfers
+        if fee > 0 {
+            let treasury_account = payout_account(
+                &self.escrow,
+                &self.treasury.to_account_info(),
+                &self.treasury_token_account,
+            )?;
+            pay_from_escrow(
+                &self.escrow,
+                &self.escrow_token_account,
+                &self.token_mint,
+                &self.token_program,
+                treasury_account,
+                fee,
+            )?;
+        }
+        
+        for ((payee, payee_account), share) in self.escrow.payees.iter().zip(payee_accounts).zip(shares) {
+            let wallet = if payee.wallet == self.escrow.recipient {
+                rights_holder
+            } else {
+                payee.wallet
+            };
+            let expected_account = if self.escrow.is_native {
+                wallet
+            } else {
+                get_associated_token_address_with_program_id(
+                    &wallet,
+                    &self.token_mint.key(),
+                    &self.token_program.key(),
+                )
+            };
+            require!(
+                payee_account.key() == expected_account,
+                EscrowError::InvalidPayeeAccount
+            );
+            
+            if share == 0 {
+                continue;
+            }
+            
+            pay_from_escrow(
+                &self.escrow,
+                &self.escrow_token_account,
+                &self.token_mint,
+                &self.token_program,
+                payee_account.clone(),
+                share,
+            )?;
+            
+            emit!(PayeePaid {
+                escrow: self.escrow.key(),
+                milestone: self.milestone.key(),
+                payee: wallet,
+                amount: share,
+            });
+        }
+        
+        // Update escrow state
+        self.escrow.released_amount = self.escrow.released_amount
+            .checked_add(transfer_amount)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+            
+        self.escrow.current_milestone = self.escrow.current_milestone
+            .checked_add(1)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+            
+        // Check if all milestones are completed
+        if self.escrow.current_milestone >= self.escrow.milestones_count {
+            self.escrow.transition(EscrowStatus::Completed)?;
+        }
+        
+        emit!(FundsReleased {
+            escrow: self.escrow.key(),
+            milestone: self.milestone.key(),
+            amount: transfer_amount,
+            fee,
+            recipient: rights_holder,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> ReclaimExpiredMilestone<'info> {
+    pub fn reclaim(&mut self) -> Result<()> {
+        self.escrow.require_status(&[EscrowStatus::InProgress])?;
+        require!(
+            self.milestone.index == self.escrow.current_milestone,
+            EscrowError::InvalidMilestoneIndex
+        );
+        require!(!self.milestone.completed, EscrowError::MilestoneAlreadyCompleted);
+        require!(!self.milestone.disputed, EscrowError::MilestoneDisputed);
+        
+        let clock = Clock::get()?;
+        
+        // The recipient keeps the grace period on top of the deadline to submit
+        let reclaimable_at = self.milestone.deadline.saturating_add(self.escrow.grace_period);
+        require!(
+            clock.unix_timestamp >= reclaimable_at,
+            EscrowError::DeadlineNotReached
+        );
+        
+        let reclaim_amount = self.milestone.amount;
+        
+        require!(
+            unallocated_balance(&self.escrow, &self.escrow_token_account)? >= reclaim_amount,
+            EscrowError::InsufficientFunds
+        );
+        
+        refund_creator(
+            &mut self.escrow,
+            &self.escrow_token_account,
+            &self.token_mint,
+            &self.token_program,
+            &self.creator.to_account_info(),
+            &self.creator_token_account,
+            reclaim_amount,
+        )?;
+        
+        // Update escrow state
+        self.escrow.current_milestone = self.escrow.current_milestone
+            .checked_add(1)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+            
+        if self.escrow.current_milestone >= self.escrow.milestones_count {
+            self.escrow.transition(EscrowStatus::Completed)?;
+        }
+        
+        emit!(MilestoneReclaimed {
+            escrow: self.escrow.key(),
+            milestone: self.milestone.key(),
+            milestone_index: self.milestone.index,
+            amount: reclaim_amount,
+            creator: self.creator.key(),
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> WithdrawVested<'info> {
+    pub fn withdraw(&mut self) -> Result<()> {
+        self.escrow.require_status(&[EscrowStatus::InProgress])?;
+        let schedule = self.escrow.vesting.ok_or(EscrowError::NotVestingEscrow)?;
+        
+        let vested = schedule.vested_amount(Clock::get()?.unix_timestamp)?;
+        let amount = vested.saturating_sub(self.escrow.released_amount);
+        require!(amount > 0, EscrowError::NothingVested);
+        
+        let recipient_account = payout_account(
+            &self.escrow,
+            &self.recipient.to_account_info(),
+            &self.recipient_token_account,
+        )?;
+        pay_from_escrow(
+            &self.escrow,
+            &self.escrow_token_account,
+            &self.token_mint,
+            &self.token_program,
+            recipient_account,
+            amount,
+        )?;
+        
+        self.escrow.released_amount = vested;
+        if self.escrow.released_amount >= schedule.amount {
+            self.escrow.transition(EscrowStatus::Completed)?;
+        }
+        
+        emit!(VestedWithdrawn {
+            escrow: self.escrow.key(),
+            recipient: self.recipient.key(),
+            amount,
+            released_amount: vested,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> CancelVesting<'info> {
+    pub fn cancel(&mut self) -> Result<()> {
+        self.escrow.require_status(&[EscrowStatus::InProgress])?;
+        let schedule = self.escrow.vesting.ok_or(EscrowError::NotVestingEscrow)?;
+        
+        // Whatever has vested still goes to the recipient; the unvested remainder is refunded to funders
+        let vested = schedule.vested_amount(Clock::get()?.unix_timestamp)?;
+        let recipient_amount = vested.saturating_sub(self.escrow.released_amount);
+        let refund_pool = unallocated_balance(&self.escrow, &self.escrow_token_account)?
+            .checked_sub(recipient_amount)
+            .ok_or(EscrowError::InsufficientFunds)?;
+        
+        if recipient_amount > 0 {
+            let recipient_account = payout_account(
+                &self.escrow,
+                &self.recipient.to_account_info(),
+                &self.recipient_token_account,
+            )?;
+            pay_from_escrow(
+                &self.escrow,
+                &self.escrow_token_account,
+                &self.token_mint,
+                &self.token_program,
+                recipient_account,
+                recipient_amount,
+            )?;
+        }
+        
+        self.escrow.released_amount = vested;
+        self.escrow.refund_pool = self.escrow.refund_pool
+            .checked_add(refund_pool)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+        self.escrow.transition(EscrowStatus::Cancelled)?;
+        
+        emit!(VestingCancelled {
+            escrow: self.escrow.key(),
+            recipient_amount,
+            refund_pool,
+        });
+        
+        Ok(())
+    }
 }
 
-// Implementation of the escrow program
-impl<'info> InitializeEscrow<'info> {
-    pub fn initialize(
-        &mut self,
-        bump: u8,
-        milestones_count: u8,
-    ) -> Result<()> {
-        require!(milestones_count > 0, EscrowError::InvalidMilestoneIndex);
+impl<'info> TransferRecipient<'info> {
+    pub fn transfer(&mut self) -> Result<()> {
+        self.escrow.require_status(&[
+            EscrowStatus::Draft,
+            EscrowStatus::AwaitingFunding,
+            EscrowStatus::InProgress,
+        ])?;
+        require!(self.escrow.receipt_mint.is_none(), EscrowError::ReceiptOutstanding);
+        if self.escrow.transfer_requires_creator {
+            require!(
+                self.creator.as_ref().map_or(false, |creator| creator.key() == self.escrow.creator),
+                EscrowError::CreatorConsentRequired
+            );
+        }
         
-        let clock = Clock::get()?;
+        let previous_recipient = self.recipient.key();
+        let new_recipient = self.new_recipient.key();
+        require!(
+            new_recipient != previous_recipient
+                && new_recipient != self.escrow.creator
+                && new_recipient != self.escrow.arbitrator
+                && !self.escrow.verifiers.contains(&new_recipient)
+                && !self.escrow.payees.iter().any(|payee| payee.wallet == new_recipient),
+            EscrowError::InvalidNewRecipient
+        );
+        // A new recipient taking over a lamport payee share is held to the same rent check as at creation
+        if self.escrow.is_native && self.escrow.payees.iter().any(|payee| payee.wallet == previous_recipient) {
+            require!(
+                rent_exempt_after(&self.new_recipient.to_account_info(), 0)?,
+                EscrowError::PayoutBelowRentExemption
+            );
+        }
         
-        self.escrow.creator = self.creator.key();
-        self.escrow.recipient = self.recipient.key();
-        self.escrow.token_mint = self.token_mint.key();
-        self.escrow.total_amount = 0;
-        self.escrow.released_amount = 0;
-        self.escrow.milestones_count = milestones_count;
-        self.escrow.current_milestone = 0;
-        self.escrow.is_active = true;
-        self.escrow.created_at = clock.unix_timestamp;
-        self.escrow.bump = bump;
+        // The recipient's share of every future release follows the payment rights
+        for payee in self.escrow.payees.iter_mut() {
+            if payee.wallet == previous_recipient {
+                payee.wallet = new_recipient;
+            }
+        }
         
-        emit!(EscrowCreated {
+        // A cancellation proposed by the previous recipient no longer speaks for the holder
+        if self.escrow.cancel_proposed_by == Some(previous_recipient) {
+            self.escrow.cancel_proposed_by = None;
+        }
+        self.escrow.recipient = new_recipient;
+        
+        emit!(RecipientTransferred {
             escrow: self.escrow.key(),
-            creator: self.creator.key(),
-            recipient: self.recipient.key(),
-            amount: 0,
+            previous_recipient,
+            new_recipient,
         });
         
         Ok(())
     }
 }
 
-impl<'info> AddMilestone<'info> {
-    pub fn add_milestone(
-        &mut self,
-        bump: u8,
-        amount: u64,
-        deadline: i64,
-    ) -> Result<()> {
-        require!(self.escrow.is_active, EscrowError::EscrowNotActive);
+impl<'info> MintReceipt<'info> {
+    pub fn mint(&mut self, uri: String) -> Result<()> {
+        self.escrow.require_status(&[
+            EscrowStatus::Draft,
+            EscrowStatus::AwaitingFunding,
+            EscrowStatus::InProgress,
+        ])?;
+        // Releases only route a payee share to the holder, so a recipient outside the payees has nothing to sell
+        let recipient = self.escrow.recipient;
         require!(
-            self.milestone.index < self.escrow.milestones_count,
-            EscrowError::InvalidMilestoneIndex
+            self.escrow.receipt_mint.is_none()
+                && self.escrow.vesting.is_none()
+                && self.escrow.payees.iter().any(|payee| payee.wallet == recipient),
+            EscrowError::ReceiptUnavailable
         );
         
-        self.milestone.escrow = self.escrow.key();
-        self.milestone.index = self.escrow.current_milestone;
-        self.milestone.amount = amount;
-        self.milestone.deadline = deadline;
-        self.milestone.completed = false;
-        self.milestone.verified = false;
-        self.milestone.verified_at = None;
-        self.milestone.verified_by = None;
-        self.milestone.bump = bump;
+        let receipt_mint = self.receipt_mint.key();
+        require_keys_eq!(self.token_metadata_program.key(), mpl_token_metadata::ID);
+        require_keys_eq!(self.metadata.key(), get_metadata_account(&receipt_mint));
+        require_keys_eq!(self.master_edition.key(), get_master_edition_account(&receipt_mint));
+        
+        let escrow_id = self.escrow.escrow_id.to_le_bytes();
+        let seeds = &[
+            ESCROW_PDA_SEED,
+            self.escrow.creator.as_ref(),
+            self.escrow.initial_recipient.as_ref(),
+            escrow_id.as_ref(),
+            &[self.escrow.bump],
+        ];
+        
+        let signer = &[&seeds[..]];
+        
+        // The escrow mints the single receipt token, then hands its mint authority to the master edition
+        let cpi_accounts = MintTo {
+            mint: self.receipt_mint.to_account_info(),
+            to: self.receipt_token_account.to_account_info(),
+            authority: self.escrow.to_account_info(),
+        };
+        
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::mint_to(cpi_ctx, 1)?;
+        
+        invoke_signed(
+            &create_metadata_accounts_v3(
+                mpl_token_metadata::ID,
+                self.metadata.key(),
+                receipt_mint,
+                self.escrow.key(),
+                self.recipient.key(),
+                self.escrow.key(),
+                RECEIPT_NAME.to_string(),
+                RECEIPT_SYMBOL.to_string(),
+                uri,
+                None,
+                0,
+                true,
+                false,
+                None,
+                None,
+                None,
+            ),
+            &[
+                self.metadata.to_account_info(),
+                self.receipt_mint.to_account_info(),
+                self.escrow.to_account_info(),
+                self.recipient.to_account_info(),
+                self.system_program.to_account_info(),
+                self.rent.to_account_info(),
+            ],
+            signer,
+        )?;
+        
+        invoke_signed(
+            &create_master_edition_v3(
+                mpl_token_metadata::ID,
+                self.master_edition.key(),
+                receipt_mint,
+                self.escrow.key(),
+                self.escrow.key(),
+                self.metadata.key(),
+                self.recipient.key(),
+                Some(0),
+            ),
+            &[
+                self.master_edition.to_account_info(),
+                self.receipt_mint.to_account_info(),
+                self.escrow.to_account_info(),
+                self.recipient.to_account_info(),
+                self.metadata.to_account_info(),
+                self.token_program.to_account_info(),
+                self.system_program.to_account_info(),
+                self.rent.to_account_info(),
+            ],
+            signer,
+        )?;
+        
+        self.escrow.receipt_mint = Some(receipt_mint);
+        
+        emit!(ReceiptMinted {
+            escrow: self.escrow.key(),
+            receipt_mint,
+            holder: self.recipient.key(),
+        });
         
-        self.escrow.total_amount = self.escrow.total_amount.checked_add(amount)
-            .ok_or(EscrowError::InvalidMilestoneState)?;
-            
         Ok(())
     }
 }
 
-impl<'info> FundEscrow<'info> {
-    pub fn fund(&mut self, amount: u64) -> Result<()> {
-        require!(self.escrow.is_active, EscrowError::EscrowNotActive);
-        
-        // Transfer tokens from funder to escrow
-        let cpi_accounts = Transfer {
-            from: self.funder_token_account.to_account_info(),
-            to: self.escrow_token_account.to_account_info(),
-            authority: self.funder.to_account_info(),
+impl<'info> ProposeCancel<'info> {
+    pub fn propose(&mut self, recipient_share_bps: u16) -> Result<()> {
+        self.escrow.require_status(OPEN_ESCROW_STATUSES)?;
+        require!(
+            self.proposer.key() == self.escrow.creator
+                || self.proposer.key() == self.escrow.rights_holder(&self.receipt_token_account)?,
+            EscrowError::NotEscrowParty
+        );
+        require!(
+            recipient_share_bps <= MAX_BASIS_POINTS,
+            EscrowError::InvalidCancelShare
+        );
+        
+        // A new proposal from either party replaces any pending one
+        self.escrow.cancel_proposed_by = Some(self.proposer.key());
+        self.escrow.cancel_recipient_share_bps = recipient_share_bps;
+        
+        emit!(CancelProposed {
+            escrow: self.escrow.key(),
+            proposer: self.proposer.key(),
+            recipient_share_bps,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> AcceptCancel<'info> {
+    pub fn accept(&mut self, recipient_share_bps: u16) -> Result<()> {
+        self.escrow.require_status(OPEN_ESCROW_STATUSES)?;
+        
+        // The recipient's share goes to, and their consent comes from, whoever holds the rights now
+        let rights_holder = self.escrow.rights_holder(&self.receipt_token_account)?;
+        require_keys_eq!(self.recipient.key(), rights_holder, EscrowError::InvalidRightsHolder);
+        
+        let proposer = self.escrow.cancel_proposed_by
+            .filter(|proposer| *proposer == self.escrow.creator || *proposer == rights_holder)
+            .ok_or(EscrowError::CancelNotProposed)?;
+        let counterparty = if proposer == self.escrow.creator {
+            rights_holder
+        } else {
+            self.escrow.creator
         };
+        require!(
+            self.acceptor.key() == counterparty,
+            EscrowError::InvalidCancelAcceptor
+        );
         
-        let cpi_program = self.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, amount)?;
+        // Guard against the proposal being swapped out before acceptance lands
+        require!(
+            recipient_share_bps == self.escrow.cancel_recipient_share_bps,
+            EscrowError::CancelTermsMismatch
+        );
         
-        self.escrow.total_amount = self.escrow.total_amount.checked_add(amount)
-            .ok_or(EscrowError::InsufficientFunds)?;
+        // Split whatever is still held by the escrow and not already set aside for funders
+        let remaining = unallocated_balance(&self.escrow, &self.escrow_token_account)?;
+        let recipient_amount: u64 = (remaining as u128)
+            .checked_mul(recipient_share_bps as u128)
+            .and_then(|v| v.checked_div(MAX_BASIS_POINTS as u128))
+            .and_then(|v| v.try_into().ok())
+            .ok_or(EscrowError::InvalidCancelShare)?;
+        let refund_pool = remaining
+            .checked_sub(recipient_amount)
+            .ok_or(EscrowError::InvalidCancelShare)?;
+        
+        if recipient_amount > 0 {
+            let recipient_account = payout_account(
+                &self.escrow,
+                &self.recipient.to_account_info(),
+                &self.recipient_token_account,
+            )?;
+            pay_from_escrow(
+                &self.escrow,
+                &self.escrow_token_account,
+                &self.token_mint,
+                &self.token_program,
+                recipient_account,
+                recipient_amount,
+            )?;
+        }
+        
+        // Update escrow state; the rest stays in the escrow until funders claim it pro rata
+        self.escrow.released_amount = self.escrow.released_amount
+            .checked_add(recipient_amount)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
             
+        self.escrow.refund_pool = self.escrow.refund_pool
+            .checked_add(refund_pool)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+        self.escrow.cancel_proposed_by = None;
+        self.escrow.transition(EscrowStatus::Cancelled)?;
+        
+        emit!(EscrowCancelled {
+            escrow: self.escrow.key(),
+            recipient_amount,
+            refund_pool,
+        });
+        
         Ok(())
     }
 }
 
-impl<'info> CompleteMilestone<'info> {
-    pub fn complete(&mut self) -> Result<()> {
-        require!(self.escrow.is_active, EscrowError::EscrowNotActive);
+impl<'info> CloseEscrow<'info> {
+    // Milestone PDAs in index order, then a (dispute, raised_by) pair per dispute, are passed
+    // as remaining accounts since their number varies per escrow
+    pub fn close(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        require!(!self.escrow.is_active(), EscrowError::EscrowStillActive);
         require!(
-            self.milestone.index == self.escrow.current_milestone,
-            EscrowError::InvalidMilestoneIndex
+            escrow_balance(&self.escrow, &self.escrow_token_account)? == 0,
+            EscrowError::EscrowNotSettled
         );
-        require!(!self.milestone.completed, EscrowError::MilestoneAlreadyCompleted);
         
-        let clock = Clock::get()?;
+        let milestones_added = self.escrow.milestones_added as usize;
+        let disputes_raised = self.escrow.disputes_raised as usize;
+        require!(
+            remaining_accounts.len() == milestones_added + 2 * disputes_raised,
+            EscrowError::CloseAccountsMismatch
+        );
+        let (milestone_accounts, dispute_accounts) = remaining_accounts.split_at(milestones_added);
         
-        // Mark milestone as completed
-        self.milestone.completed = true;
-        self.milestone.verified = true;
-        self.milestone.verified_at = Some(clock.unix_timestamp);
-        self.milestone.verified_by = Some(self.verifier.key());
+        // Close the escrow token account and return its rent to the creator;
+        // a native escrow's reserve goes back with the escrow account itself
+        if let Some(escrow_token_account) = &self.escrow_token_account {
+            let escrow_id = self.escrow.escrow_id.to_le_bytes();
+            let seeds = &[
+                ESCROW_PDA_SEED,
+                self.escrow.creator.as_ref(),
+                self.escrow.initial_recipient.as_ref(),
+                escrow_id.as_ref(),
+                &[self.escrow.bump],
+            ];
+            
+            let signer = &[&seeds[..]];
+            
+            let cpi_accounts = CloseAccount {
+                account: escrow_token_account.to_account_info(),
+                destination: self.creator.to_account_info(),
+                authority: self.escrow.to_account_info(),
+            };
+            
+            let cpi_program = self.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token_interface::close_account(cpi_ctx)?;
+        }
+        
+        // Close every milestone PDA; matching indices rules out duplicates
+        for (index, account_info) in milestone_accounts.iter().enumerate() {
+            require!(account_info.is_writable, EscrowError::InvalidEscrowAccount);
+            
+            let milestone: Account<'info, Milestone> = Account::try_from(account_info)?;
+            require!(
+                milestone.escrow == self.escrow.key() && milestone.index as usize == index,
+                EscrowError::CloseAccountsMismatch
+            );
+            
+            milestone.close(self.creator.to_account_info())?;
+        }
+        
+        // Dispute rent goes back to the party that raised it; a closed dispute
+        // cannot be deserialized again, so passing one twice fails
+        for pair in dispute_accounts.chunks(2) {
+            let (account_info, raised_by) = (&pair[0], &pair[1]);
+            require!(account_info.is_writable, EscrowError::InvalidEscrowAccount);
+            
+            let dispute: Account<'info, Dispute> = Account::try_from(account_info)?;
+            require!(
+                dispute.escrow == self.escrow.key() && dispute.raised_by == raised_by.key(),
+                EscrowError::CloseAccountsMismatch
+            );
+            
+            dispute.close(raised_by.clone())?;
+        }
+        
+        // The emergency request's rent belongs to the signer who opened it
+        if self.multisig.owner == &crate::ID {
+            let multisig = Multisig::try_deserialize(&mut &self.multisig.try_borrow_data()?[..])?;
+            let requester = self.requester.as_ref()
+                .ok_or(EscrowError::CloseAccountsMismatch)?;
+            require!(
+                requester.key() == multisig.requester,
+                EscrowError::CloseAccountsMismatch
+            );
+            
+            // Close it the way Anchor's `close` constraint does: drain, hand back to the system program, empty
+            let multisig_info = self.multisig.to_account_info();
+            let requester_info = requester.to_account_info();
+            let rent = multisig_info.lamports();
+            **requester_info.try_borrow_mut_lamports()? = requester_info.lamports()
+                .checked_add(rent)
+                .ok_or(EscrowError::InvalidMilestoneState)?;
+            **multisig_info.try_borrow_mut_lamports()? = 0;
+            multisig_info.assign(&system_program::ID);
+            multisig_info.realloc(0, false)?;
+        }
         
-        emit!(MilestoneCompleted {
+        emit!(EscrowClosed {
             escrow: self.escrow.key(),
-            milestone: self.milestone.key(),
-            milestone_index: self.milestone.index,
-            amount: self.milestone.amount,
+            creator: self.creator.key(),
+            milestones_closed: milestone_accounts.len() as u8,
         });
         
         Ok(())
     }
 }
 
-impl<'info> ReleaseFunds<'info> {
-    pub fn release(&mut self) -> Result<()> {
-        require!(self.escrow.is_active, EscrowError::EscrowNotActive);
+impl<'info> RaiseDispute<'info> {
+    pub fn raise(&mut self, bump: u8) -> Result<()> {
+        self.escrow.require_status(&[EscrowStatus::InProgress])?;
+        require!(
+            self.party.key() == self.escrow.creator || self.party.key() == self.escrow.recipient,
+            EscrowError::NotEscrowParty
+        );
         require!(
             self.milestone.index == self.escrow.current_milestone,
             EscrowError::InvalidMilestoneIndex
         );
-        require!(self.milestone.completed, EscrowError::MilestoneNotCompleted);
-        require!(self.milestone.verified, EscrowError::MilestoneNotCompleted);
+        require!(!self.milestone.disputed, EscrowError::MilestoneDisputed);
         
         let clock = Clock::get()?;
         
-        // Verify deadline has passed if milestone is not verified
-        if !self.milestone.verified {
+        // The creator cannot block a submission that has already been deemed accepted
+        if self.party.key() == self.escrow.creator {
             require!(
-                clock.unix_timestamp >= self.milestone.deadline,
-                EscrowError::DeadlineNotReached
+                !self.milestone.review_period_elapsed(self.escrow.review_period, clock.unix_timestamp),
+                EscrowError::ReviewPeriodElapsed
             );
         }
         
-        // Transfer tokens from escrow to recipient
-        let transfer_amount = self.milestone.amount;
+        self.milestone.disputed = true;
+        self.escrow.disputes_raised = self.escrow.disputes_raised
+            .checked_add(1)
+            .ok_or(EscrowError::InvalidMilestoneState)?;
+        self.escrow.transition(EscrowStatus::Disputed)?;
+        
+        self.dispute.escrow = self.escrow.key();
+        self.dispute.milestone = self.milestone.key();
+        self.dispute.raised_by = self.party.key();
+        self.dispute.raised_at = clock.unix_timestamp;
+        self.dispute.status = DisputeStatus::Open;
+        self.dispute.creator_evidence = None;
+        self.dispute.recipient_evidence = None;
+        self.dispute.recipient_share_bps = 0;
+        self.dispute.resolved_at = None;
+        self.dispute.bump = bump;
+        
+        emit!(DisputeRaised {
+            escrow: self.escrow.key(),
+            milestone: self.milestone.key(),
+            dispute: self.dispute.key(),
+            raised_by: self.party.key(),
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> SubmitEvidence<'info> {
+    pub fn submit(&mut self, evidence_hash: [u8; 32]) -> Result<()> {
+        self.escrow.require_status(&[EscrowStatus::Disputed])?;
+        require!(
+            self.dispute.status == DisputeStatus::Open,
+            EscrowError::DisputeNotOpen
+        );
+        
+        // Only the latest submission per party is kept on-chain; the event log holds the history
+        if self.party.key() == self.escrow.creator {
+            self.dispute.creator_evidence = Some(evidence_hash);
+        } else if self.party.key() == self.escrow.recipient {
+            self.dispute.recipient_evidence = Some(evidence_hash);
+        } else {
+            return err!(EscrowError::NotEscrowParty);
+        }
+        
+        emit!(EvidenceSubmitted {
+            dispute: self.dispute.key(),
+            submitted_by: self.party.key(),
+            evidence_hash,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> ResolveDispute<'info> {
+    pub fn resolve(&mut self, recipient_share_bps: u16) -> Result<()> {
+        self.escrow.require_status(&[EscrowStatus::Disputed])?;
+        require!(
+            self.dispute.status == DisputeStatus::Open,
+            EscrowError::DisputeNotOpen
+        );
+        require!(
+            recipient_share_bps <= MAX_BASIS_POINTS,
+            EscrowError::InvalidDisputeShare
+        );
+        
+        // The recipient's award follows the receipt like any other release
+        let rights_holder = self.escrow.rights_holder(&self.receipt_token_account)?;
+        require_keys_eq!(self.recipient.key(), rights_holder, EscrowError::InvalidRightsHolder);
+        
+        let clock = Clock::get()?;
+        
+        // Split the milestone amount between recipient and creator
+        let milestone_amount = self.milestone.amount;
+        let recipient_amount: u64 = (milestone_amount as u128)
+            .checked_mul(recipient_share_bps as u128)
+            .and_then(|v| v.checked_div(MAX_BASIS_POINTS as u128))
+            .and_then(|v| v.try_into().ok())
+            .ok_or(EscrowError::InvalidDisputeShare)?;
+        let creator_amount = milestone_amount
+            .checked_sub(recipient_amount)
+            .ok_or(EscrowError::InvalidDisputeShare)?;
         
-        // Verify escrow has sufficient balance
         require!(
-            self.escrow_token_account.amount >= transfer_amount,
+            unallocated_balance(&self.escrow, &self.escrow_token_account)? >= milestone_amount,
             EscrowError::InsufficientFunds
         );
         
-        // Perform the transfer
-        let seeds = &[
-            ESCROW_PDA_SEED,
-            self.escrow.creator.as_ref(),
-            self.escrow.recipient.as_ref(),
-            &[self.escrow.bump],
-        ];
+        if recipient_amount > 0 {
+            let recipient_account = payout_account(
+                &self.escrow,
+                &self.recipient.to_account_info(),
+                &self.recipient_token_account,
+            )?;
+            pay_from_escrow(
+                &self.escrow,
+                &self.escrow_token_account,
+                &self.token_mint,
+                &self.token_program,
+                recipient_account,
+                recipient_amount,
+            )?;
+        }
         
-        let signer = &[&seeds[..]];
+        refund_creator(
+            &mut self.escrow,
+            &self.escrow_token_account,
+            &self.token_mint,
+            &self.token_program,
+            &self.creator.to_account_info(),
+            &self.creator_token_account,
+            creator_amount,
+        )?;
         
-        let cpi_accounts = Transfer {
-            from: self.escrow_token_account.to_account_info(),
-            to: self.recipient_token_account.to_account_info(),
-            authority: self.escrow.to_account_info(),
-        };
+        // The arbitrator's ruling settles the milestone
+        self.dispute.status = DisputeStatus::Resolved;
+        self.dispute.recipient_share_bps = recipient_share_bps;
+        self.dispute.resolved_at = Some(clock.unix_timestamp);
         
-        let cpi_program = self.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        token::transfer(cpi_ctx, transfer_amount)?;
+        self.milestone.disputed = false;
+        self.milestone.completed = true;
+        self.milestone.verified = true;
+        self.milestone.verified_at = Some(clock.unix_timestamp);
+        self.milestone.verified_by = Some(self.arbitrator.key());
         
-        // Update escrow state
         self.escrow.released_amount = self.escrow.released_amount
-            .checked_add(transfer_amount)
+            .checked_add(recipient_amount)
             .ok_or(EscrowError::InvalidMilestoneState)?;
             
         self.escrow.current_milestone = self.escrow.current_milestone
             .checked_add(1)
             .ok_or(EscrowError::InvalidMilestoneState)?;
             
-        // Check if all milestones are completed
         if self.escrow.current_milestone >= self.escrow.milestones_count {
-            self.escrow.is_active = false;
+            self.escrow.transition(EscrowStatus::Completed)?;
+        } else {
+            self.escrow.transition(EscrowStatus::InProgress)?;
         }
         
-        emit!(FundsReleased {
+        emit!(DisputeResolved {
             escrow: self.escrow.key(),
             milestone: self.milestone.key(),
-            amount: transfer_amount,
-            recipient: self.recipient.key(),
+            dispute: self.dispute.key(),
+            recipient_amount,
+            creator_amount,
         });
         
         Ok(())
@@ -476,24 +3468,59 @@ impl<'info> ReleaseFunds<'info> {
 }
 
 impl<'info> InitiateEmergencyWithdrawal<'info> {
-    pub fn initiate(&mut self, bump: u8, signers: Vec<Pubkey>) -> Result<()> {
-        require!(self.escrow.is_active, EscrowError::EscrowNotActive);
+    pub fn initiate(&mut self, bump: u8) -> Result<()> {
+        self.escrow.require_status(OPEN_ESCROW_STATUSES)?;
         require!(
-            signers.len() == 3 && signers.contains(&self.requester.key()),
+            self.escrow.emergency_signers.contains(&self.requester.key()),
             EscrowError::InvalidSigner
         );
         
         self.multisig.escrow = self.escrow.key();
-        self.multisig.signers = signers;
-        self.multisig.threshold = MULTISIG_THRESHOLD;
+        self.multisig.requester = self.requester.key();
+        self.multisig.signers = self.escrow.emergency_signers.clone();
+        self.multisig.threshold = self.escrow.emergency_threshold;
         self.multisig.signed_by = vec![self.requester.key()];
         self.multisig.executed = false;
+        self.multisig.objected = false;
+        self.multisig.objection_upheld = None;
+        self.multisig.paused_escrow = false;
+        self.multisig.created_at = Clock::get()?.unix_timestamp;
+        self.multisig.approved_at = None;
         self.multisig.bump = bump;
         
         emit!(EmergencyWithdrawalRequested {
             escrow: self.escrow.key(),
             requester: self.requester.key(),
-            amount: self.escrow.total_amount - self.escrow.released_amount,
+            amount: self.escrow.funded_amount
+                .saturating_sub(self.escrow.released_amount)
+                .saturating_sub(self.escrow.refunded_amount),
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> CancelEmergencyWithdrawal<'info> {
+    pub fn cancel(&mut self) -> Result<()> {
+        require!(!self.multisig.executed, EscrowError::AlreadyExecuted);
+        
+        // The requester can withdraw the request at any time; anyone can clear a stale or refused one
+        let expired = self.multisig.is_expired(Clock::get()?.unix_timestamp);
+        let refused = self.multisig.objection_upheld == Some(true);
+        require!(
+            expired || refused || self.canceller.key() == self.multisig.requester,
+            EscrowError::EmergencyRequestActive
+        );
+        
+        // Withdrawing a request still under objection ends the dispute it opened
+        if self.multisig.objection_pending() && self.multisig.paused_escrow {
+            self.escrow.transition(EscrowStatus::InProgress)?;
+        }
+        
+        emit!(EmergencyWithdrawalCancelled {
+            escrow: self.escrow.key(),
+            cancelled_by: self.canceller.key(),
+            expired,
         });
         
         Ok(())
@@ -502,11 +3529,18 @@ impl<'info> InitiateEmergencyWithdrawal<'info> {
 
 impl<'info> SignEmergencyWithdrawal<'info> {
     pub fn sign(&mut self) -> Result<()> {
+        self.escrow.require_status(OPEN_ESCROW_STATUSES)?;
         require!(!self.multisig.executed, EscrowError::AlreadyExecuted);
         require!(
             self.multisig.signers.contains(&self.signer.key()),
             EscrowError::InvalidSigner
         );
+        
+        let clock = Clock::get()?;
+        require!(
+            !self.multisig.is_expired(clock.unix_timestamp),
+            EscrowError::EmergencyRequestExpired
+        );
         require!(
             !self.multisig.signed_by.contains(&self.signer.key()),
             EscrowError::AlreadySigned
@@ -514,14 +3548,111 @@ impl<'info> SignEmergencyWithdrawal<'info> {
         
         self.multisig.signed_by.push(self.signer.key());
         
-        // Check if we have enough signatures
-        if (self.multisig.signed_by.len() as u8) >= self.multisig.threshold {
-            self.multisig.executed = true;
-            self.escrow.is_active = false;
-            // In a real implementation, you would transfer remaining funds back to creator
-            // This is simplified for the example
+        // Reaching the threshold starts the recipient's objection window rather than paying out
+        if (self.multisig.signed_by.len() as u8) >= self.multisig.threshold
+            && self.multisig.approved_at.is_none()
+        {
+            self.multisig.approved_at = Some(clock.unix_timestamp);
+            
+            emit!(EmergencyWithdrawalApproved {
+                escrow: self.escrow.key(),
+                executable_at: clock.unix_timestamp.saturating_add(self.escrow.emergency_delay),
+            });
+        }
+        
+        Ok(())
+    }
+}
+
+impl<'info> ObjectEmergencyWithdrawal<'info> {
+    pub fn object(&mut self) -> Result<()> {
+        self.escrow.require_status(OPEN_ESCROW_STATUSES)?;
+        require!(!self.multisig.executed, EscrowError::AlreadyExecuted);
+        require!(!self.multisig.objected, EscrowError::EmergencyRequestObjected);
+        let executable_at = self.multisig.executable_at(self.escrow.emergency_delay)
+            .ok_or(EscrowError::EmergencyNotApproved)?;
+        require!(
+            Clock::get()?.unix_timestamp < executable_at,
+            EscrowError::ObjectionWindowClosed
+        );
+        
+        // The objection puts a running escrow into dispute until the arbitrator rules on it;
+        // one already disputed over a milestone stays so, and the ruling is still required
+        self.multisig.objected = true;
+        if self.escrow.status == EscrowStatus::InProgress {
+            self.escrow.transition(EscrowStatus::Disputed)?;
+            self.multisig.paused_escrow = true;
+        }
+        
+        emit!(EmergencyWithdrawalObjected {
+            escrow: self.escrow.key(),
+            recipient: self.recipient.key(),
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> ResolveEmergencyObjection<'info> {
+    pub fn resolve(&mut self, uphold: bool) -> Result<()> {
+        require!(!self.multisig.executed, EscrowError::AlreadyExecuted);
+        require!(self.multisig.objection_pending(), EscrowError::NoPendingObjection);
+        
+        // Upholding refuses the withdrawal for good; dismissing lets it execute once the delay has passed
+        self.multisig.objection_upheld = Some(uphold);
+        if self.multisig.paused_escrow {
+            self.escrow.transition(EscrowStatus::InProgress)?;
         }
         
+        emit!(EmergencyObjectionResolved {
+            escrow: self.escrow.key(),
+            arbitrator: self.arbitrator.key(),
+            upheld: uphold,
+        });
+        
+        Ok(())
+    }
+}
+
+impl<'info> ExecuteEmergencyWithdrawal<'info> {
+    pub fn execute(&mut self) -> Result<()> {
+        self.escrow.require_status(WITHDRAWABLE_ESCROW_STATUSES)?;
+        require!(!self.multisig.executed, EscrowError::AlreadyExecuted);
+        require!(!self.multisig.objection_pending(), EscrowError::EmergencyObjectionPending);
+        require!(
+            self.multisig.objection_upheld != Some(true),
+            EscrowError::EmergencyRequestObjected
+        );
+        let executable_at = self.multisig.executable_at(self.escrow.emergency_delay)
+            .ok_or(EscrowError::EmergencyNotApproved)?;
+        require!(
+            Clock::get()?.unix_timestamp >= executable_at,
+            EscrowError::EmergencyDelayActive
+        );
+        
+        self.multisig.executed = true;
+        self.escrow.transition(EscrowStatus::EmergencyWithdrawn)?;
+        
+        // Return everything still held by the escrow to the creator, or to the funders if crowdfunded
+        let withdraw_amount = unallocated_balance(&self.escrow, &self.escrow_token_account)?;
+        
+        refund_creator(
+            &mut self.escrow,
+            &self.escrow_token_account,
+            &self.token_mint,
+            &self.token_program,
+            &self.creator.to_account_info(),
+            &self.creator_token_account,
+            withdraw_amount,
+        )?;
+        
+        emit!(EmergencyWithdrawalExecuted {
+            escrow: self.escrow.key(),
+            executor: self.executor.key(),
+            amount: withdraw_amount,
+            recipient: self.creator.key(),
+        });
+        
         Ok(())
     }
 }
@@ -48,13 +48,47 @@ pub mod dappr {
 
     // Escrow instructions
     
+    /// Create the program config holding the protocol fee and treasury
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        bump: u8,
+        fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        escrow::InitializeConfig::initialize(ctx, bump, fee_bps, treasury)
+    }
+    
+    /// Update the protocol fee and treasury (admin only)
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        escrow::UpdateConfig::update(ctx, fee_bps, treasury)
+    }
+    
+    /// Allow escrows to be created with a mint (admin only)
+    pub fn add_allowed_mint(
+        ctx: Context<UpdateConfig>,
+        mint: Pubkey,
+    ) -> Result<()> {
+        escrow::UpdateConfig::add_allowed_mint(ctx, mint)
+    }
+    
+    /// Stop new escrows from being created with a mint (admin only)
+    pub fn remove_allowed_mint(
+        ctx: Context<UpdateConfig>,
+        mint: Pubkey,
+    ) -> Result<()> {
+        escrow::UpdateConfig::remove_allowed_mint(ctx, mint)
+    }
+    
     /// Initialize a new escrow
     pub fn initialize_escrow(
         ctx: Context<InitializeEscrow>,
-        bump: u8,
-        milestones_count: u8,
+        params: InitializeEscrowParams,
     ) -> Result<()> {
-        escrow::InitializeEscrow::initialize(ctx, bump, milestones_count)
+        escrow::InitializeEscrow::initialize(ctx, params, ctx.remaining_accounts)
     }
     
     /// Add a milestone to an escrow
@@ -67,35 +101,166 @@ pub mod dappr {
         escrow::AddMilestone::add_milestone(ctx, bump, amount, deadline)
     }
     
-    /// Fund an escrow with tokens
+    /// Propose a new amount and deadline for an unreleased milestone
+    pub fn propose_milestone_amendment(
+        ctx: Context<ProposeMilestoneAmendment>,
+        amount: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        escrow::ProposeMilestoneAmendment::propose(ctx, amount, deadline)
+    }
+    
+    /// Accept the other party's milestone amendment, refunding any excess and re-checking the escrow's funding
+    pub fn accept_milestone_amendment(
+        ctx: Context<AcceptMilestoneAmendment>,
+        amount: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        escrow::AcceptMilestoneAmendment::accept(ctx, amount, deadline)
+    }
+    
+    /// Fund an escrow with tokens, recording the deposit against the funder's contribution
     pub fn fund_escrow(
         ctx: Context<FundEscrow>,
         amount: u64,
+        bump: u8,
+    ) -> Result<()> {
+        escrow::FundEscrow::fund(ctx, amount, bump)
+    }
+    
+    /// Claim a funder's pro-rata share of the refund pool, settling it once the escrow has finished
+    pub fn claim_refund(
+        ctx: Context<ClaimRefund>,
+    ) -> Result<()> {
+        escrow::ClaimRefund::claim(ctx)
+    }
+    
+    /// Submit the deliverable for the current milestone
+    pub fn submit_milestone(
+        ctx: Context<SubmitMilestone>,
+        deliverable_hash: Vec<u8>,
+    ) -> Result<()> {
+        escrow::SubmitMilestone::submit(ctx, deliverable_hash)
+    }
+    
+    /// Approve a submitted milestone; it is verified once the verifier threshold is met
+    pub fn approve_milestone(
+        ctx: Context<ApproveMilestone>,
+    ) -> Result<()> {
+        escrow::ApproveMilestone::approve(ctx)
+    }
+    
+    /// Reject a submitted milestone with a reason code
+    pub fn reject_milestone(
+        ctx: Context<RejectMilestone>,
+        reason_code: u16,
+    ) -> Result<()> {
+        escrow::RejectMilestone::reject(ctx, reason_code)
+    }
+    
+    /// Release funds for an approved milestone, or one left unreviewed past the review period
+    pub fn release_funds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReleaseFunds<'info>>,
     ) -> Result<()> {
-        escrow::FundEscrow::fund(ctx, amount)
+        escrow::ReleaseFunds::release(ctx, ctx.remaining_accounts)
     }
     
-    /// Mark a milestone as completed
-    pub fn complete_milestone(
-        ctx: Context<CompleteMilestone>,
+    /// Return an expired, unsubmitted milestone's funds to the creator
+    pub fn reclaim_expired_milestone(
+        ctx: Context<ReclaimExpiredMilestone>,
     ) -> Result<()> {
-        escrow::CompleteMilestone::complete(ctx)
+        escrow::ReclaimExpiredMilestone::reclaim(ctx)
     }
     
-    /// Release funds for a completed milestone
-    pub fn release_funds(
-        ctx: Context<ReleaseFunds>,
+    /// Withdraw everything vested so far from a vesting escrow
+    pub fn withdraw_vested(
+        ctx: Context<WithdrawVested>,
     ) -> Result<()> {
-        escrow::ReleaseFunds::release(ctx)
+        escrow::WithdrawVested::withdraw(ctx)
+    }
+    
+    /// Stop a vesting escrow, paying out what has vested and refunding the rest
+    pub fn cancel_vesting(
+        ctx: Context<CancelVesting>,
+    ) -> Result<()> {
+        escrow::CancelVesting::cancel(ctx)
+    }
+    
+    /// Hand the recipient's payment rights to another wallet
+    pub fn transfer_recipient(
+        ctx: Context<TransferRecipient>,
+    ) -> Result<()> {
+        escrow::TransferRecipient::transfer(ctx)
+    }
+    
+    /// Mint a 1-of-1 receipt token entitling its holder to the recipient's releases
+    pub fn mint_receipt(
+        ctx: Context<MintReceipt>,
+        uri: String,
+    ) -> Result<()> {
+        escrow::MintReceipt::mint(ctx, uri)
+    }
+    
+    /// Propose a mutual cancellation splitting the remaining balance
+    pub fn propose_cancel(
+        ctx: Context<ProposeCancel>,
+        recipient_share_bps: u16,
+    ) -> Result<()> {
+        escrow::ProposeCancel::propose(ctx, recipient_share_bps)
+    }
+    
+    /// Accept the other party's cancellation proposal and settle the escrow
+    pub fn accept_cancel(
+        ctx: Context<AcceptCancel>,
+        recipient_share_bps: u16,
+    ) -> Result<()> {
+        escrow::AcceptCancel::accept(ctx, recipient_share_bps)
+    }
+    
+    /// Close a finished escrow and its milestone, multisig and token accounts
+    pub fn close_escrow<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseEscrow<'info>>,
+    ) -> Result<()> {
+        escrow::CloseEscrow::close(ctx, ctx.remaining_accounts)
+    }
+    
+    /// Raise a dispute against the current milestone
+    pub fn raise_dispute(
+        ctx: Context<RaiseDispute>,
+        bump: u8,
+    ) -> Result<()> {
+        escrow::RaiseDispute::raise(ctx, bump)
+    }
+    
+    /// Submit evidence for an open dispute
+    pub fn submit_evidence(
+        ctx: Context<SubmitEvidence>,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        escrow::SubmitEvidence::submit(ctx, evidence_hash)
+    }
+    
+    /// Resolve a dispute by splitting the milestone amount
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        recipient_share_bps: u16,
+    ) -> Result<()> {
+        escrow::ResolveDispute::resolve(ctx, recipient_share_bps)
     }
     
     /// Initiate emergency withdrawal process
     pub fn initiate_emergency_withdrawal(
         ctx: Context<InitiateEmergencyWithdrawal>,
         bump: u8,
-        signers: Vec<Pubkey>,
     ) -> Result<()> {
-        escrow::InitiateEmergencyWithdrawal::initiate(ctx, bump, signers)
+        escrow::InitiateEmergencyWithdrawal::initiate(ctx, bump)
+    }
+    
+    /// Cancel a pending or expired emergency withdrawal
+    pub fn cancel_emergency_withdrawal(
+        ctx: Context<CancelEmergencyWithdrawal>,
+    ) -> Result<()> {
+        escrow::CancelEmergencyWithdrawal::cancel(ctx)
     }
     
     /// Sign an emergency withdrawal
@@ -104,6 +269,28 @@ pub mod dappr {
     ) -> Result<()> {
         escrow::SignEmergencyWithdrawal::sign(ctx)
     }
+    
+    /// Object to an approved emergency withdrawal during its window, putting it to the arbitrator
+    pub fn object_emergency_withdrawal(
+        ctx: Context<ObjectEmergencyWithdrawal>,
+    ) -> Result<()> {
+        escrow::ObjectEmergencyWithdrawal::object(ctx)
+    }
+    
+    /// Rule on the recipient's objection to an emergency withdrawal (arbitrator only)
+    pub fn resolve_emergency_objection(
+        ctx: Context<ResolveEmergencyObjection>,
+        uphold: bool,
+    ) -> Result<()> {
+        escrow::ResolveEmergencyObjection::resolve(ctx, uphold)
+    }
+    
+    /// Execute an approved emergency withdrawal once its delay has passed
+    pub fn execute_emergency_withdrawal(
+        ctx: Context<ExecuteEmergencyWithdrawal>,
+    ) -> Result<()> {
+        escrow::ExecuteEmergencyWithdrawal::execute(ctx)
+    }
 }
 
 /// Accounts required by the initialize instruction
@@ -121,12 +308,44 @@ pub use escrow::{
     Escrow,
     Milestone,
     Multisig,
+    Payee,
+    VestingSchedule,
+    InitializeEscrowParams,
+    MilestoneAmendment,
+    Contribution,
+    Dispute,
+    Config,
+    DisputeStatus,
+    EscrowStatus,
+    FundingStatus,
+    InitializeConfig,
+    UpdateConfig,
     InitializeEscrow,
     AddMilestone,
+    ProposeMilestoneAmendment,
+    AcceptMilestoneAmendment,
     FundEscrow,
-    CompleteMilestone,
+    ClaimRefund,
+    SubmitMilestone,
+    ApproveMilestone,
+    RejectMilestone,
     ReleaseFunds,
+    ReclaimExpiredMilestone,
+    WithdrawVested,
+    CancelVesting,
+    TransferRecipient,
+    MintReceipt,
+    ProposeCancel,
+    AcceptCancel,
+    CloseEscrow,
+    RaiseDispute,
+    SubmitEvidence,
+    ResolveDispute,
     InitiateEmergencyWithdrawal,
+    CancelEmergencyWithdrawal,
     SignEmergencyWithdrawal,
+    ObjectEmergencyWithdrawal,
+    ResolveEmergencyObjection,
+    ExecuteEmergencyWithdrawal,
     EscrowError,
 };
@@ -33,16 +33,20 @@ pub const DAPPR_USD_URI: &str = "https://dappr-token-metadata.s3.amazonaws.com/d
 
 // Token PDA seeds
 pub const MINT_SEED: &[u8] = b"mint";
+pub const DAPPR_GOV_MINT_SEED: &[u8] = b"dappr_gov";
+pub const DAPPR_USD_MINT_SEED: &[u8] = b"dappr_usd";
 
 #[derive(Accounts)]
 pub struct InitializeTokens<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     
-    // Token mints
+    // Token mints, at fixed PDAs so each can only ever be created once
     #[account(
         init,
         payer = payer,
+        seeds = [DAPPR_GOV_MINT_SEED],
+        bump,
         mint::decimals = DAPPR_GOV_DECIMALS,
         mint::authority = mint_authority.key(),
     )]
@@ -51,6 +55,8 @@ pub struct InitializeTokens<'info> {
     #[account(
         init,
         payer = payer,
+        seeds = [DAPPR_USD_MINT_SEED],
+        bump,
         mint::decimals = DAPPR_USD_DECIMALS,
         mint::authority = mint_authority.key(),
     )]
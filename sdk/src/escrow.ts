@@ -1,14 +1,40 @@
 import { Program, web3, BN } from '@project-serum/anchor';
-import { PublicKey, Connection, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { PublicKey, Connection, Keypair, ParsedAccountData, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
 import { Token, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from '@solana/spl-token';
 import { Dappr } from './dappr';
 import { DapprClient } from './index';
-import { Escrow, Milestone, Multisig } from './types';
+import { Config, Contribution, Dispute, DisputeStatus, Escrow, EscrowStatus, FundingStatus, InitializeEscrowParams, Milestone, Multisig } from './types';
 
 // Constants
 export const ESCROW_PDA_SEED = 'escrow';
 export const MILESTONE_PDA_SEED = 'milestone';
 export const MULTISIG_PDA_SEED = 'multisig';
+export const DISPUTE_PDA_SEED = 'dispute';
+export const CONFIG_PDA_SEED = 'config';
+export const CONTRIBUTION_PDA_SEED = 'contribution';
+export const RECEIPT_MINT_SEED = 'receipt';
+export const MINT_AUTHORITY_SEED = 'mint';
+export const DAPPR_GOV_MINT_SEED = 'dappr_gov';
+export const DAPPR_USD_MINT_SEED = 'dappr_usd';
+
+export const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+export const BPF_UPGRADEABLE_LOADER_ID = new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111');
+
+// Default time a creator has to review a submitted milestone (14 days)
+export const DEFAULT_REVIEW_PERIOD = 14 * 24 * 60 * 60;
+
+// Default time the recipient has to object to an approved emergency withdrawal (3 days)
+export const DEFAULT_EMERGENCY_DELAY = 3 * 24 * 60 * 60;
+
+// Anchor decodes enum fields as `{ variantName: {} }`; the SDK's string enums use the variant names
+const decodeEnum = <T>(value: any): T =>
+  (typeof value === 'string' ? value : Object.keys(value)[0]) as unknown as T;
+
+const decodeEscrow = (account: any): Escrow => ({
+  ...account,
+  status: decodeEnum<EscrowStatus>(account.status),
+  fundingStatus: decodeEnum<FundingStatus>(account.fundingStatus),
+});
 
 export class EscrowClient {
   private program: Program<Dappr>;
@@ -23,13 +49,20 @@ export class EscrowClient {
 
   /**
    * Derive the escrow PDA
+   *
+   * `escrowId` distinguishes concurrent escrows between the same creator and recipient.
    */
-  async getEscrowPDA(creator: PublicKey, recipient: PublicKey): Promise<[PublicKey, number]> {
+  async getEscrowPDA(
+    creator: PublicKey,
+    recipient: PublicKey,
+    escrowId: number | BN = 0
+  ): Promise<[PublicKey, number]> {
     return PublicKey.findProgramAddress(
       [
         Buffer.from(ESCROW_PDA_SEED),
         creator.toBuffer(),
         recipient.toBuffer(),
+        new BN(escrowId).toArrayLike(Buffer, 'le', 8),
       ],
       this.program.programId
     );
@@ -62,26 +95,281 @@ export class EscrowClient {
     );
   }
 
+  /**
+   * Derive the program config PDA
+   */
+  async getConfigPDA(): Promise<[PublicKey, number]> {
+    return PublicKey.findProgramAddress(
+      [Buffer.from(CONFIG_PDA_SEED)],
+      this.program.programId
+    );
+  }
+
+  /**
+   * Derive the dispute PDA for a milestone
+   */
+  async getDisputePDA(milestone: PublicKey): Promise<[PublicKey, number]> {
+    return PublicKey.findProgramAddress(
+      [
+        Buffer.from(DISPUTE_PDA_SEED),
+        milestone.toBuffer(),
+      ],
+      this.program.programId
+    );
+  }
+
+  /**
+   * Derive the PDA recording a funder's deposits into an escrow
+   */
+  async getContributionPDA(escrow: PublicKey, funder: PublicKey): Promise<[PublicKey, number]> {
+    return PublicKey.findProgramAddress(
+      [
+        Buffer.from(CONTRIBUTION_PDA_SEED),
+        escrow.toBuffer(),
+        funder.toBuffer(),
+      ],
+      this.program.programId
+    );
+  }
+
+  /**
+   * Derive the receipt mint PDA for an escrow
+   */
+  async getReceiptMintPDA(escrow: PublicKey): Promise<[PublicKey, number]> {
+    return PublicKey.findProgramAddress(
+      [
+        Buffer.from(RECEIPT_MINT_SEED),
+        escrow.toBuffer(),
+      ],
+      this.program.programId
+    );
+  }
+
+  /**
+   * Token program that owns a mint: the legacy Token program or Token-2022
+   */
+  async getTokenProgram(tokenMint: PublicKey): Promise<PublicKey> {
+    const mintInfo = await this.connection.getAccountInfo(tokenMint);
+    if (!mintInfo) {
+      throw new Error(`Mint ${tokenMint.toBase58()} not found`);
+    }
+    return mintInfo.owner;
+  }
+
+  /**
+   * Account holding an escrow's funds: its associated token account, or the escrow itself when native
+   */
+  async getEscrowTokenAccount(escrow: PublicKey): Promise<PublicKey> {
+    const { tokenMint, isNative } = await this.getEscrow(escrow);
+    if (isNative) {
+      return escrow;
+    }
+    return Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      await this.getTokenProgram(tokenMint),
+      tokenMint,
+      escrow,
+      true
+    );
+  }
+
+  /**
+   * Create the program config; the caller becomes its admin
+   *
+   * Only the program's upgrade authority may call this.
+   * `feeBps` of every milestone release goes to `treasury`'s associated token account.
+   * The allow-list of mints escrows may be created with starts with the DAPPR_USD mint, which must already exist.
+   */
+  async initializeConfig(
+    feeBps: number,
+    treasury: PublicKey
+  ): Promise<{ config: PublicKey; tx: string }> {
+    const [config, bump] = await this.getConfigPDA();
+    const [programData] = await PublicKey.findProgramAddress(
+      [this.program.programId.toBuffer()],
+      BPF_UPGRADEABLE_LOADER_ID
+    );
+    const [dapprUsdMint] = await PublicKey.findProgramAddress(
+      [Buffer.from(DAPPR_USD_MINT_SEED)],
+      this.program.programId
+    );
+    
+    const tx = await this.program.rpc.initializeConfig(
+      bump,
+      feeBps,
+      treasury,
+      {
+        accounts: {
+          admin: this.wallet.publicKey,
+          config,
+          dapprUsdMint,
+          program: this.program.programId,
+          programData,
+          systemProgram: SystemProgram.programId,
+        },
+      }
+    );
+
+    return { config, tx };
+  }
+
+  /**
+   * Update the protocol fee and treasury as the config admin
+   */
+  async updateConfig(
+    feeBps: number,
+    treasury: PublicKey
+  ): Promise<string> {
+    const [config] = await this.getConfigPDA();
+    
+    const tx = await this.program.rpc.updateConfig(
+      feeBps,
+      treasury,
+      {
+        accounts: {
+          admin: this.wallet.publicKey,
+          config,
+        },
+      }
+    );
+
+    return tx;
+  }
+
+  /**
+   * Allow new escrows to be created with `mint` as the config admin
+   */
+  async addAllowedMint(
+    mint: PublicKey
+  ): Promise<string> {
+    const [config] = await this.getConfigPDA();
+    
+    const tx = await this.program.rpc.addAllowedMint(
+      mint,
+      {
+        accounts: {
+          admin: this.wallet.publicKey,
+          config,
+        },
+      }
+    );
+
+    return tx;
+  }
+
+  /**
+   * Remove `mint` from the allow-list as the config admin; existing escrows are unaffected
+   */
+  async removeAllowedMint(
+    mint: PublicKey
+  ): Promise<string> {
+    const [config] = await this.getConfigPDA();
+    
+    const tx = await this.program.rpc.removeAllowedMint(
+      mint,
+      {
+        accounts: {
+          admin: this.wallet.publicKey,
+          config,
+        },
+      }
+    );
+
+    return tx;
+  }
+
   /**
    * Initialize a new escrow
+   *
+   * `emergencyThreshold` of the `emergencySigners` must sign to return unreleased funds to the creator.
+   * The recipient then has `emergencyDelay` seconds to object before the withdrawal can execute.
+   * Leave `verifiers` empty to have the creator approve milestones alone.
+   * Leave `payees` empty to pay every release to the recipient; otherwise shares must sum to 10,000 bps.
+   * Submissions left unreviewed for `reviewPeriod` seconds can be released by anyone.
+   * Milestones not submitted within `gracePeriod` seconds of their deadline can be reclaimed.
+   * Set `isNative` (with `NATIVE_MINT` as `tokenMint`) to hold and pay out lamports in the escrow account instead of tokens.
+   * `tokenMint` must be on the config allow-list; legacy Token and Token-2022 mints are both supported.
+   * Pass a `vesting` schedule (with `milestonesCount` 0) to unlock funds linearly instead of by milestone.
+   * Set `fundingDeadline` to let funders claim refunds if the escrow is not fully funded by then.
+   * Set `transferRequiresCreator` to make recipient transfers need the creator's signature as well.
    */
   async initializeEscrow(
     recipient: PublicKey,
-    milestonesCount: number
+    tokenMint: PublicKey,
+    {
+      milestonesCount,
+      arbitrator,
+      emergencySigners,
+      emergencyThreshold,
+      emergencyDelay = DEFAULT_EMERGENCY_DELAY,
+      escrowId = 0,
+      verifiers = [],
+      verifierThreshold = 0,
+      reviewPeriod = DEFAULT_REVIEW_PERIOD,
+      gracePeriod = 0,
+      payees = [],
+      isNative = false,
+      vesting = null,
+      fundingDeadline = null,
+      transferRequiresCreator = false,
+    }: InitializeEscrowParams
   ): Promise<{ escrow: PublicKey; tx: string }> {
-    const [escrow, bump] = await this.getEscrowPDA(this.wallet.publicKey, recipient);
+    const [escrow, bump] = await this.getEscrowPDA(this.wallet.publicKey, recipient, escrowId);
+    const [config] = await this.getConfigPDA();
+    const tokenProgram = isNative ? TOKEN_PROGRAM_ID : await this.getTokenProgram(tokenMint);
+    
+    // Without a payee list the recipient receives every release
+    const payeeWallets = payees.length > 0 ? payees.map(payee => payee.wallet) : [recipient];
+    
+    // Native escrows hold lamports and have no token account
+    const escrowTokenAccount = isNative
+      ? null
+      : await Token.getAssociatedTokenAddress(
+          ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram,
+          tokenMint,
+          escrow,
+          true
+        );
     
     const tx = await this.program.rpc.initializeEscrow(
-      bump,
-      milestonesCount,
+      {
+        escrowId: new BN(escrowId),
+        bump,
+        milestonesCount,
+        arbitrator,
+        verifiers,
+        verifierThreshold,
+        reviewPeriod: new BN(reviewPeriod),
+        gracePeriod: new BN(gracePeriod),
+        emergencySigners,
+        emergencyThreshold,
+        emergencyDelay: new BN(emergencyDelay),
+        payees,
+        isNative,
+        vesting,
+        fundingDeadline: fundingDeadline === null ? null : new BN(fundingDeadline),
+        transferRequiresCreator,
+      },
       {
         accounts: {
           creator: this.wallet.publicKey,
           recipient,
+          tokenMint,
+          config,
           escrow,
+          escrowTokenAccount,
           systemProgram: SystemProgram.programId,
+          tokenProgram,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           rent: SYSVAR_RENT_PUBKEY,
         },
+        // A native escrow checks that every payee wallet is already rent-exempt
+        remainingAccounts: isNative ? payeeWallets.map(pubkey => ({
+          pubkey,
+          isWritable: false,
+          isSigner: false,
+        })) : [],
       }
     );
 
@@ -90,6 +378,9 @@ export class EscrowClient {
 
   /**
    * Add a milestone to an escrow
+   *
+   * Milestones are indexed in the order they are added, so `milestoneIndex` must equal
+   * the escrow's current `milestonesAdded` count.
    */
   async addMilestone(
     escrow: PublicKey,
@@ -117,24 +408,31 @@ export class EscrowClient {
   }
 
   /**
-   * Fund an escrow with tokens
+   * Fund an escrow with tokens, or with lamports for native escrows
+   *
+   * Deposits are recorded against the caller's contribution, which sizes their refund if the
+   * escrow is cancelled or misses its funding deadline.
    */
   async fundEscrow(
     escrow: PublicKey,
     tokenMint: PublicKey,
     amount: number
   ): Promise<string> {
+    const { isNative } = await this.getEscrow(escrow);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
+    const [contribution, bump] = await this.getContributionPDA(escrow, this.wallet.publicKey);
+    
     // Get or create token accounts
     const creatorTokenAccount = await Token.getAssociatedTokenAddress(
       ASSOCIATED_TOKEN_PROGRAM_ID,
-      TOKEN_PROGRAM_ID,
+      tokenProgram,
       tokenMint,
       this.wallet.publicKey
     );
 
     const escrowTokenAccount = await Token.getAssociatedTokenAddress(
       ASSOCIATED_TOKEN_PROGRAM_ID,
-      TOKEN_PROGRAM_ID,
+      tokenProgram,
       tokenMint,
       escrow,
       true
@@ -142,14 +440,16 @@ export class EscrowClient {
 
     const tx = await this.program.rpc.fundEscrow(
       new BN(amount),
+      bump,
       {
         accounts: {
           funder: this.wallet.publicKey,
           escrow,
+          contribution,
           tokenMint,
-          funderTokenAccount: creatorTokenAccount,
-          escrowTokenAccount,
-          tokenProgram: TOKEN_PROGRAM_ID,
+          funderTokenAccount: isNative ? null : creatorTokenAccount,
+          escrowTokenAccount: isNative ? null : escrowTokenAccount,
+          tokenProgram,
           systemProgram: SystemProgram.programId,
         },
       }
@@ -159,20 +459,42 @@ export class EscrowClient {
   }
 
   /**
-   * Mark a milestone as completed
+   * Claim the caller's refund from a cancelled escrow, or one that missed its funding deadline
+   *
+   * Funders receive their contribution's share of the unreleased funds and the contribution
+   * account is closed.
    */
-  async completeMilestone(
-    escrow: PublicKey,
-    milestoneIndex: number
+  async claimRefund(
+    escrow: PublicKey
   ): Promise<string> {
-    const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
+    const { tokenMint, isNative } = await this.getEscrow(escrow);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
+    const [contribution] = await this.getContributionPDA(escrow, this.wallet.publicKey);
+    
+    const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      escrow,
+      true
+    );
+
+    const funderTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      this.wallet.publicKey
+    );
     
-    const tx = await this.program.rpc.completeMilestone({
+    const tx = await this.program.rpc.claimRefund({
       accounts: {
-        verifier: this.wallet.publicKey,
+        funder: this.wallet.publicKey,
         escrow,
-        milestone,
-        clock: web3.SYSVAR_CLOCK_PUBKEY,
+        contribution,
+        tokenMint,
+        escrowTokenAccount: isNative ? null : escrowTokenAccount,
+        funderTokenAccount: isNative ? null : funderTokenAccount,
+        tokenProgram,
       },
     });
 
@@ -180,90 +502,127 @@ export class EscrowClient {
   }
 
   /**
-   * Release funds for a completed milestone
+   * Propose a new amount and deadline for an unreleased milestone, as the creator or the holder of the recipient's rights
    */
-  async releaseFunds(
+  async proposeMilestoneAmendment(
     escrow: PublicKey,
     milestoneIndex: number,
-    tokenMint: PublicKey,
-    recipient: PublicKey
+    amount: number,
+    deadline: number
   ): Promise<string> {
     const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
+    const { receiptTokenAccount } = await this.getRightsHolder(escrow);
+    
+    const tx = await this.program.rpc.proposeMilestoneAmendment(
+      new BN(amount),
+      new BN(deadline),
+      {
+        accounts: {
+          proposer: this.wallet.publicKey,
+          escrow,
+          milestone,
+          receiptTokenAccount,
+        },
+      }
+    );
+
+    return tx;
+  }
+
+  /**
+   * Accept the other party's milestone amendment
+   *
+   * `amount` and `deadline` must match the proposal. If the new milestone total is no longer
+   * covered by deposits the escrow returns to `AwaitingFunding` until it is topped up.
+   */
+  async acceptMilestoneAmendment(
+    escrow: PublicKey,
+    milestoneIndex: number,
+    amount: number,
+    deadline: number
+  ): Promise<string> {
+    const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
+    const { receiptTokenAccount } = await this.getRightsHolder(escrow);
+    
+    // A decrease refunds the creator any deposits it leaves uncommitted
+    const { creator, tokenMint, isNative } = await this.getEscrow(escrow);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
     
-    // Get or create token accounts
     const escrowTokenAccount = await Token.getAssociatedTokenAddress(
       ASSOCIATED_TOKEN_PROGRAM_ID,
-      TOKEN_PROGRAM_ID,
+      tokenProgram,
       tokenMint,
       escrow,
       true
     );
 
-    const recipientTokenAccount = await Token.getAssociatedTokenAddress(
+    const creatorTokenAccount = await Token.getAssociatedTokenAddress(
       ASSOCIATED_TOKEN_PROGRAM_ID,
-      TOKEN_PROGRAM_ID,
+      tokenProgram,
       tokenMint,
-      recipient,
-      true
+      creator
+    );
+    
+    const tx = await this.program.rpc.acceptMilestoneAmendment(
+      new BN(amount),
+      new BN(deadline),
+      {
+        accounts: {
+          acceptor: this.wallet.publicKey,
+          escrow,
+          milestone,
+          receiptTokenAccount,
+          creator,
+          tokenMint,
+          escrowTokenAccount: isNative ? null : escrowTokenAccount,
+          creatorTokenAccount: isNative ? null : creatorTokenAccount,
+          tokenProgram,
+        },
+      }
     );
-
-    const tx = await this.program.rpc.releaseFunds({
-      accounts: {
-        releaser: this.wallet.publicKey,
-        escrow,
-        recipient,
-        milestone,
-        tokenMint,
-        escrowTokenAccount,
-        recipientTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        clock: web3.SYSVAR_CLOCK_PUBKEY,
-      },
-    });
 
     return tx;
   }
 
   /**
-   * Initiate emergency withdrawal process
+   * Submit a milestone deliverable as the recipient
    */
-  async initiateEmergencyWithdrawal(
+  async submitMilestone(
     escrow: PublicKey,
-    creator: PublicKey,
-    signers: PublicKey[]
-  ): Promise<{ multisig: PublicKey; tx: string }> {
-    const [multisig, bump] = await this.getMultisigPDA(escrow);
+    milestoneIndex: number,
+    deliverableHash: Buffer
+  ): Promise<string> {
+    const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
     
-    const tx = await this.program.rpc.initiateEmergencyWithdrawal(
-      bump,
-      signers,
+    const tx = await this.program.rpc.submitMilestone(
+      deliverableHash,
       {
         accounts: {
-          requester: this.wallet.publicKey,
+          recipient: this.wallet.publicKey,
           escrow,
-          creator,
-          multisig,
-          systemProgram: SystemProgram.programId,
+          milestone,
         },
       }
     );
 
-    return { multisig, tx };
+    return tx;
   }
 
   /**
-   * Sign an emergency withdrawal
+   * Approve a submitted milestone as one of the escrow verifiers
    */
-  async signEmergencyWithdrawal(
-    escrow: PublicKey
+  async approveMilestone(
+    escrow: PublicKey,
+    milestoneIndex: number
   ): Promise<string> {
-    const [multisig] = await this.getMultisigPDA(escrow);
+    const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
     
-    const tx = await this.program.rpc.signEmergencyWithdrawal({
+    const tx = await this.program.rpc.approveMilestone({
       accounts: {
-        signer: this.wallet.publicKey,
+        verifier: this.wallet.publicKey,
         escrow,
-        multisig,
+        milestone,
+        clock: web3.SYSVAR_CLOCK_PUBKEY,
       },
     });
 
@@ -271,17 +630,755 @@ export class EscrowClient {
   }
 
   /**
-   * Fetch escrow account data
+   * Reject a submitted milestone as one of the escrow verifiers
    */
-  async getEscrow(escrow: PublicKey): Promise<Escrow> {
-    return this.program.account.escrow.fetch(escrow);
+  async rejectMilestone(
+    escrow: PublicKey,
+    milestoneIndex: number,
+    reasonCode: number
+  ): Promise<string> {
+    const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
+    
+    const tx = await this.program.rpc.rejectMilestone(
+      reasonCode,
+      {
+        accounts: {
+          verifier: this.wallet.publicKey,
+          escrow,
+          milestone,
+        },
+      }
+    );
+
+    return tx;
   }
 
   /**
-   * Fetch milestone account data
+   * Release funds for an approved milestone, or one left unreviewed past the review period
+   *
+   * The protocol fee goes to the treasury and the rest is split between the escrow payees'
+   * associated token accounts, or their wallets for native escrows. The recipient's share goes
+   * to the receipt holder once a receipt has been minted.
    */
-  async getMilestone(milestone: PublicKey): Promise<Milestone> {
-    return this.program.account.milestone.fetch(milestone);
+  async releaseFunds(
+    escrow: PublicKey,
+    milestoneIndex: number,
+    tokenMint: PublicKey,
+    recipient: PublicKey
+  ): Promise<string> {
+    const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
+    
+    // Get or create token accounts
+    const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      escrow,
+      true
+    );
+
+    // Payee accounts are passed in the order the escrow lists its payees
+    // Once a receipt is minted its holder takes the recipient's place among them
+    const { payees, isNative, receiptMint, recipient: currentRecipient } = await this.getEscrow(escrow);
+    const receipt = receiptMint ? await this.getReceiptHolder(receiptMint) : null;
+    const payeeWallets = payees.map(payee =>
+      receipt && payee.wallet.equals(currentRecipient) ? receipt.holder : payee.wallet
+    );
+    const payeeAccounts = isNative
+      ? payeeWallets
+      : await Promise.all(
+        payeeWallets.map(wallet => Token.getAssociatedTokenAddress(
+          ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram,
+          tokenMint,
+          wallet,
+          true
+        ))
+      );
+
+    const [config] = await this.getConfigPDA();
+    const { treasury } = await this.getConfig();
+    const treasuryTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      treasury,
+      true
+    );
+
+    const tx = await this.program.rpc.releaseFunds({
+      accounts: {
+        releaser: this.wallet.publicKey,
+        escrow,
+        recipient,
+        milestone,
+        tokenMint,
+        escrowTokenAccount: isNative ? null : escrowTokenAccount,
+        config,
+        treasury,
+        treasuryTokenAccount: isNative ? null : treasuryTokenAccount,
+        receiptTokenAccount: receipt ? receipt.tokenAccount : null,
+        tokenProgram,
+        clock: web3.SYSVAR_CLOCK_PUBKEY,
+      },
+      remainingAccounts: payeeAccounts.map(pubkey => ({
+        pubkey,
+        isWritable: true,
+        isSigner: false,
+      })),
+    });
+
+    return tx;
+  }
+
+  /**
+   * Reclaim the funds of a milestone whose deadline passed without a submission
+   */
+  async reclaimExpiredMilestone(
+    escrow: PublicKey,
+    milestoneIndex: number,
+    tokenMint: PublicKey
+  ): Promise<string> {
+    const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
+    const { isNative } = await this.getEscrow(escrow);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
+    
+    const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      escrow,
+      true
+    );
+
+    const creatorTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      this.wallet.publicKey
+    );
+    
+    const tx = await this.program.rpc.reclaimExpiredMilestone({
+      accounts: {
+        creator: this.wallet.publicKey,
+        escrow,
+        milestone,
+        tokenMint,
+        escrowTokenAccount: isNative ? null : escrowTokenAccount,
+        creatorTokenAccount: isNative ? null : creatorTokenAccount,
+        tokenProgram,
+      },
+    });
+
+    return tx;
+  }
+
+  /**
+   * Withdraw everything vested so far from a vesting escrow as the recipient
+   */
+  async withdrawVested(
+    escrow: PublicKey
+  ): Promise<string> {
+    const { tokenMint, isNative } = await this.getEscrow(escrow);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
+    
+    const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      escrow,
+      true
+    );
+
+    const recipientTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      this.wallet.publicKey,
+      true
+    );
+    
+    const tx = await this.program.rpc.withdrawVested({
+      accounts: {
+        recipient: this.wallet.publicKey,
+        escrow,
+        tokenMint,
+        escrowTokenAccount: isNative ? null : escrowTokenAccount,
+        recipientTokenAccount: isNative ? null : recipientTokenAccount,
+        tokenProgram,
+      },
+    });
+
+    return tx;
+  }
+
+  /**
+   * Cancel a vesting escrow as the creator
+   *
+   * The recipient is paid whatever has vested and the unvested remainder is left for funders to
+   * claim with `claimRefund`.
+   */
+  async cancelVesting(
+    escrow: PublicKey
+  ): Promise<string> {
+    const { recipient, tokenMint, isNative } = await this.getEscrow(escrow);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
+    
+    const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      escrow,
+      true
+    );
+
+    const recipientTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      recipient,
+      true
+    );
+
+    const tx = await this.program.rpc.cancelVesting({
+      accounts: {
+        creator: this.wallet.publicKey,
+        escrow,
+        recipient,
+        tokenMint,
+        escrowTokenAccount: isNative ? null : escrowTokenAccount,
+        recipientTokenAccount: isNative ? null : recipientTokenAccount,
+        tokenProgram,
+      },
+    });
+
+    return tx;
+  }
+
+  /**
+   * Hand the caller's recipient payment rights to `newRecipient`
+   *
+   * The new recipient takes over the recipient's payee share and every recipient action.
+   * Pass the creator's keypair as `creator` when the escrow requires their consent.
+   */
+  async transferRecipient(
+    escrow: PublicKey,
+    newRecipient: PublicKey,
+    creator: Keypair | null = null
+  ): Promise<string> {
+    const tx = await this.program.rpc.transferRecipient({
+      accounts: {
+        recipient: this.wallet.publicKey,
+        escrow,
+        creator: creator ? creator.publicKey : null,
+        newRecipient,
+      },
+      signers: creator ? [creator] : [],
+    });
+
+    return tx;
+  }
+
+  /**
+   * Mint the escrow's 1-of-1 receipt token to the caller as recipient
+   *
+   * From then on the recipient's share of every milestone release is paid to whoever holds the
+   * receipt, so the right to future payouts can be sold or pledged. `uri` points at the token's
+   * off-chain metadata.
+   */
+  async mintReceipt(
+    escrow: PublicKey,
+    uri: string
+  ): Promise<{ receiptMint: PublicKey; tx: string }> {
+    const [receiptMint] = await this.getReceiptMintPDA(escrow);
+    const receiptTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      TOKEN_PROGRAM_ID,
+      receiptMint,
+      this.wallet.publicKey
+    );
+    const [metadata] = await PublicKey.findProgramAddress(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), receiptMint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    const [masterEdition] = await PublicKey.findProgramAddress(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), receiptMint.toBuffer(), Buffer.from('edition')],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    
+    const tx = await this.program.rpc.mintReceipt(
+      uri,
+      {
+        accounts: {
+          recipient: this.wallet.publicKey,
+          escrow,
+          receiptMint,
+          receiptTokenAccount,
+          metadata,
+          masterEdition,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    return { receiptMint, tx };
+  }
+
+  /**
+   * Find the current holder of an escrow receipt and the token account holding it
+   */
+  async getReceiptHolder(
+    receiptMint: PublicKey
+  ): Promise<{ holder: PublicKey; tokenAccount: PublicKey }> {
+    const { value } = await this.connection.getTokenLargestAccounts(receiptMint);
+    const holding = value.find(account => account.amount === '1');
+    if (!holding) {
+      throw new Error(`Receipt ${receiptMint.toBase58()} is not held by any account`);
+    }
+    
+    const { value: accountInfo } = await this.connection.getParsedAccountInfo(holding.address);
+    const holder = new PublicKey((accountInfo!.data as ParsedAccountData).parsed.info.owner);
+    return { holder, tokenAccount: holding.address };
+  }
+
+  /**
+   * Find who holds the recipient's rights: the recipient, or the receipt holder once a receipt is minted
+   */
+  async getRightsHolder(
+    escrow: PublicKey
+  ): Promise<{ holder: PublicKey; receiptTokenAccount: PublicKey | null }> {
+    const { recipient, receiptMint } = await this.getEscrow(escrow);
+    if (!receiptMint) {
+      return { holder: recipient, receiptTokenAccount: null };
+    }
+    
+    const { holder, tokenAccount } = await this.getReceiptHolder(receiptMint);
+    return { holder, receiptTokenAccount: tokenAccount };
+  }
+
+  /**
+   * Propose a mutual cancellation, paying the recipient `recipientShareBps` of the remaining balance
+   */
+  async proposeCancel(
+    escrow: PublicKey,
+    recipientShareBps: number
+  ): Promise<string> {
+    const { receiptTokenAccount } = await this.getRightsHolder(escrow);
+    
+    const tx = await this.program.rpc.proposeCancel(
+      recipientShareBps,
+      {
+        accounts: {
+          proposer: this.wallet.publicKey,
+          escrow,
+          receiptTokenAccount,
+        },
+      }
+    );
+
+    return tx;
+  }
+
+  /**
+   * Accept the other party's cancellation proposal and settle the escrow
+   *
+   * The recipient (or receipt holder) is paid their share; the rest is left for funders to claim with `claimRefund`.
+   */
+  async acceptCancel(
+    escrow: PublicKey,
+    recipientShareBps: number
+  ): Promise<string> {
+    const { tokenMint, isNative } = await this.getEscrow(escrow);
+    const { holder: recipient, receiptTokenAccount } = await this.getRightsHolder(escrow);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
+    
+    const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      escrow,
+      true
+    );
+
+    const recipientTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      recipient,
+      true
+    );
+    
+    const tx = await this.program.rpc.acceptCancel(
+      recipientShareBps,
+      {
+        accounts: {
+          acceptor: this.wallet.publicKey,
+          escrow,
+          recipient,
+          tokenMint,
+          escrowTokenAccount: isNative ? null : escrowTokenAccount,
+          recipientTokenAccount: isNative ? null : recipientTokenAccount,
+          receiptTokenAccount,
+          tokenProgram,
+        },
+      }
+    );
+
+    return tx;
+  }
+
+  /**
+   * Close a finished escrow, reclaiming rent from its token, milestone, dispute and multisig accounts
+   */
+  async closeEscrow(
+    escrow: PublicKey
+  ): Promise<string> {
+    const { tokenMint, isNative, milestonesAdded } = await this.getEscrow(escrow);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
+    const [multisig] = await this.getMultisigPDA(escrow);
+    const multisigInfo = await this.connection.getAccountInfo(multisig);
+    const requester = multisigInfo ? (await this.getMultisig(multisig)).requester : null;
+    
+    const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      escrow,
+      true
+    );
+
+    // Milestones go in index order, followed by each dispute and the party that raised it
+    const milestones: PublicKey[] = [];
+    const disputes: PublicKey[] = [];
+    for (let i = 0; i < milestonesAdded; i++) {
+      const [milestone] = await this.getMilestonePDA(escrow, i);
+      milestones.push(milestone);
+      
+      const [dispute] = await this.getDisputePDA(milestone);
+      if (await this.connection.getAccountInfo(dispute)) {
+        const { raisedBy } = await this.getDispute(dispute);
+        disputes.push(dispute, raisedBy);
+      }
+    }
+    
+    const tx = await this.program.rpc.closeEscrow({
+      accounts: {
+        creator: this.wallet.publicKey,
+        escrow,
+        multisig,
+        requester,
+        tokenMint,
+        escrowTokenAccount: isNative ? null : escrowTokenAccount,
+        tokenProgram,
+      },
+      remainingAccounts: [...milestones, ...disputes].map(pubkey => ({
+        pubkey,
+        isWritable: true,
+        isSigner: false,
+      })),
+    });
+
+    return tx;
+  }
+
+  /**
+   * Raise a dispute against a milestone
+   */
+  async raiseDispute(
+    escrow: PublicKey,
+    milestoneIndex: number
+  ): Promise<{ dispute: PublicKey; tx: string }> {
+    const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
+    const [dispute, bump] = await this.getDisputePDA(milestone);
+    
+    const tx = await this.program.rpc.raiseDispute(
+      bump,
+      {
+        accounts: {
+          party: this.wallet.publicKey,
+          escrow,
+          milestone,
+          dispute,
+          systemProgram: SystemProgram.programId,
+        },
+      }
+    );
+
+    return { dispute, tx };
+  }
+
+  /**
+   * Submit an evidence hash (e.g. an IPFS CID digest) for an open dispute
+   */
+  async submitEvidence(
+    escrow: PublicKey,
+    milestoneIndex: number,
+    evidenceHash: number[]
+  ): Promise<string> {
+    const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
+    const [dispute] = await this.getDisputePDA(milestone);
+    
+    const tx = await this.program.rpc.submitEvidence(
+      evidenceHash,
+      {
+        accounts: {
+          party: this.wallet.publicKey,
+          escrow,
+          dispute,
+        },
+      }
+    );
+
+    return tx;
+  }
+
+  /**
+   * Resolve a dispute as the escrow arbitrator
+   */
+  async resolveDispute(
+    escrow: PublicKey,
+    milestoneIndex: number,
+    recipientShareBps: number
+  ): Promise<string> {
+    const [milestone] = await this.getMilestonePDA(escrow, milestoneIndex);
+    const [dispute] = await this.getDisputePDA(milestone);
+    const { creator, tokenMint, isNative } = await this.getEscrow(escrow);
+    const { holder: recipient, receiptTokenAccount } = await this.getRightsHolder(escrow);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
+    
+    const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      escrow,
+      true
+    );
+
+    const recipientTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      recipient,
+      true
+    );
+
+    const creatorTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      creator
+    );
+    
+    const tx = await this.program.rpc.resolveDispute(
+      recipientShareBps,
+      {
+        accounts: {
+          arbitrator: this.wallet.publicKey,
+          escrow,
+          milestone,
+          dispute,
+          creator,
+          recipient,
+          tokenMint,
+          escrowTokenAccount: isNative ? null : escrowTokenAccount,
+          recipientTokenAccount: isNative ? null : recipientTokenAccount,
+          creatorTokenAccount: isNative ? null : creatorTokenAccount,
+          receiptTokenAccount,
+          tokenProgram,
+        },
+      }
+    );
+
+    return tx;
+  }
+
+  /**
+   * Initiate emergency withdrawal process
+   *
+   * The signer set and threshold are the ones fixed when the escrow was created.
+   */
+  async initiateEmergencyWithdrawal(
+    escrow: PublicKey,
+    creator: PublicKey
+  ): Promise<{ multisig: PublicKey; tx: string }> {
+    const [multisig, bump] = await this.getMultisigPDA(escrow);
+    
+    const tx = await this.program.rpc.initiateEmergencyWithdrawal(
+      bump,
+      {
+        accounts: {
+          requester: this.wallet.publicKey,
+          escrow,
+          creator,
+          multisig,
+          systemProgram: SystemProgram.programId,
+        },
+      }
+    );
+
+    return { multisig, tx };
+  }
+
+  /**
+   * Cancel an emergency withdrawal
+   *
+   * The requester can cancel a pending request at any time; anyone can cancel one that has expired.
+   */
+  async cancelEmergencyWithdrawal(
+    escrow: PublicKey
+  ): Promise<string> {
+    const [multisig] = await this.getMultisigPDA(escrow);
+    
+    // Rent of the closed request goes back to whoever opened it
+    const { requester } = await this.getMultisig(multisig);
+    
+    const tx = await this.program.rpc.cancelEmergencyWithdrawal({
+      accounts: {
+        canceller: this.wallet.publicKey,
+        escrow,
+        multisig,
+        requester,
+      },
+    });
+
+    return tx;
+  }
+
+  /**
+   * Sign an emergency withdrawal
+   *
+   * Reaching the threshold opens the recipient's objection window; funds move only on execution.
+   */
+  async signEmergencyWithdrawal(
+    escrow: PublicKey
+  ): Promise<string> {
+    const [multisig] = await this.getMultisigPDA(escrow);
+    
+    const tx = await this.program.rpc.signEmergencyWithdrawal({
+      accounts: {
+        signer: this.wallet.publicKey,
+        escrow,
+        multisig,
+      },
+    });
+
+    return tx;
+  }
+
+  /**
+   * Object to an approved emergency withdrawal as the recipient
+   *
+   * A running escrow moves into dispute and the withdrawal waits for the arbitrator's ruling.
+   */
+  async objectEmergencyWithdrawal(
+    escrow: PublicKey
+  ): Promise<string> {
+    const [multisig] = await this.getMultisigPDA(escrow);
+    
+    const tx = await this.program.rpc.objectEmergencyWithdrawal({
+      accounts: {
+        recipient: this.wallet.publicKey,
+        escrow,
+        multisig,
+      },
+    });
+
+    return tx;
+  }
+
+  /**
+   * Rule on the recipient's objection to an emergency withdrawal as the escrow arbitrator
+   *
+   * Upholding the objection refuses the withdrawal; dismissing it lets the withdrawal execute.
+   */
+  async resolveEmergencyObjection(
+    escrow: PublicKey,
+    uphold: boolean
+  ): Promise<string> {
+    const [multisig] = await this.getMultisigPDA(escrow);
+    
+    const tx = await this.program.rpc.resolveEmergencyObjection(
+      uphold,
+      {
+        accounts: {
+          arbitrator: this.wallet.publicKey,
+          escrow,
+          multisig,
+        },
+      }
+    );
+
+    return tx;
+  }
+
+  /**
+   * Execute an approved emergency withdrawal once its objection window has passed
+   */
+  async executeEmergencyWithdrawal(
+    escrow: PublicKey
+  ): Promise<string> {
+    const [multisig] = await this.getMultisigPDA(escrow);
+    
+    // Execution returns the remaining balance to the creator
+    const { creator, tokenMint, isNative } = await this.getEscrow(escrow);
+    const tokenProgram = await this.getTokenProgram(tokenMint);
+    
+    const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      escrow,
+      true
+    );
+
+    const creatorTokenAccount = await Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram,
+      tokenMint,
+      creator
+    );
+    
+    const tx = await this.program.rpc.executeEmergencyWithdrawal({
+      accounts: {
+        executor: this.wallet.publicKey,
+        escrow,
+        multisig,
+        creator,
+        tokenMint,
+        escrowTokenAccount: isNative ? null : escrowTokenAccount,
+        creatorTokenAccount: isNative ? null : creatorTokenAccount,
+        tokenProgram,
+      },
+    });
+
+    return tx;
+  }
+
+  /**
+   * Fetch escrow account data
+   */
+  async getEscrow(escrow: PublicKey): Promise<Escrow> {
+    return decodeEscrow(await this.program.account.escrow.fetch(escrow));
+  }
+
+  /**
+   * Fetch milestone account data
+   */
+  async getMilestone(milestone: PublicKey): Promise<Milestone> {
+    return this.program.account.milestone.fetch(milestone);
+  }
+
+  /**
+   * Fetch a funder's contribution to an escrow
+   */
+  async getContribution(escrow: PublicKey, funder: PublicKey): Promise<Contribution> {
+    const [contribution] = await this.getContributionPDA(escrow, funder);
+    return this.program.account.contribution.fetch(contribution);
   }
 
   /**
@@ -291,6 +1388,22 @@ export class EscrowClient {
     return this.program.account.multisig.fetch(multisig);
   }
 
+  /**
+   * Fetch dispute account data
+   */
+  async getDispute(dispute: PublicKey): Promise<Dispute> {
+    const account: any = await this.program.account.dispute.fetch(dispute);
+    return { ...account, status: decodeEnum<DisputeStatus>(account.status) };
+  }
+
+  /**
+   * Fetch the program config
+   */
+  async getConfig(): Promise<Config> {
+    const [config] = await this.getConfigPDA();
+    return this.program.account.config.fetch(config);
+  }
+
   /**
    * Subscribe to escrow account changes
    */
@@ -1,8 +1,8 @@
 import { Program, Provider, web3 } from '@project-serum/anchor';
 import { PublicKey, Connection, Keypair } from '@solana/web3.js';
-import { Token, TOKEN_PROGRAM_ID, AccountLayout } from '@solana/spl-token';
+import { Token, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, AccountLayout } from '@solana/spl-token';
 import { Dappr, IDL } from './dappr';
-import { EscrowClient } from './escrow';
+import { EscrowClient, MINT_AUTHORITY_SEED, DAPPR_GOV_MINT_SEED, DAPPR_USD_MINT_SEED } from './escrow';
 
 export * from './types';
 
@@ -15,7 +15,7 @@ export * from './escrow';
 // Program ID - should match the one in your program
 export const DAPPR_PROGRAM_ID = new PublicKey('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS');
 
-// Token mint addresses (will be set after initialization)
+// Token mint addresses (PDAs of the program, set once initialized)
 let DAPPR_GOV_MINT: PublicKey | null = null;
 let DAPPR_USD_MINT: PublicKey | null = null;
 
@@ -53,25 +53,34 @@ export class DapprClient {
     dapprGovMint: PublicKey;
     dapprUsdMint: PublicKey;
   }> {
-    // Derive the mint authority PDA
+    // Derive the mint authority and the mint PDAs
     const [mintAuthority] = await this.getMintAuthority();
+    [DAPPR_GOV_MINT] = await PublicKey.findProgramAddress(
+      [Buffer.from(DAPPR_GOV_MINT_SEED)],
+      this.program.programId
+    );
+    [DAPPR_USD_MINT] = await PublicKey.findProgramAddress(
+      [Buffer.from(DAPPR_USD_MINT_SEED)],
+      this.program.programId
+    );
     
     // Initialize the token mints
     const tx = await this.program.rpc.initializeTokens({
       accounts: {
         payer: this.provider.wallet.publicKey,
-        dapprGovMint: DAPPR_GOV_MINT!,
-        dapprUsdMint: DAPPR_USD_MINT!,
+        dapprGovMint: DAPPR_GOV_MINT,
+        dapprUsdMint: DAPPR_USD_MINT,
         mintAuthority,
         systemProgram: web3.SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: web3.SYSVAR_RENT_PUBKEY,
       },
     });
 
     return {
-      dapprGovMint: DAPPR_GOV_MINT!,
-      dapprUsdMint: DAPPR_USD_MINT!,
+      dapprGovMint: DAPPR_GOV_MINT,
+      dapprUsdMint: DAPPR_USD_MINT,
     };
   }
 
@@ -145,7 +154,7 @@ export class DapprClient {
   // Helper to get the mint authority PDA
   private async getMintAuthority(): Promise<[PublicKey, number]> {
     return await PublicKey.findProgramAddress(
-      [Buffer.from('mint_authority')],
+      [Buffer.from(MINT_AUTHORITY_SEED)],
       this.program.programId
     );
   }
@@ -95,49 +95,159 @@ export function isTokensBurnedEvent(event: DapprEvent): event is TokensBurnedEve
 
 // Escrow Types
 export enum EscrowStatus {
-  Initialized = 'initialized',
-  Funded = 'funded',
-  Completed = 'completed',
+  Draft = 'draft',
+  AwaitingFunding = 'awaitingFunding',
+  InProgress = 'inProgress',
+  Disputed = 'disputed',
   Cancelled = 'cancelled',
-  Disputed = 'disputed'
+  EmergencyWithdrawn = 'emergencyWithdrawn',
+  Completed = 'completed'
 }
 
-export enum MilestoneStatus {
-  Pending = 'pending',
-  Completed = 'completed',
-  Paid = 'paid',
-  Failed = 'failed'
+export enum FundingStatus {
+  Unfunded = 'unfunded',
+  PartiallyFunded = 'partiallyFunded',
+  FullyFunded = 'fullyFunded'
 }
 
 export interface Milestone {
+  escrow: PublicKey;
   index: number;
   amount: BN;
   deadline: BN;
-  status: MilestoneStatus;
-  completedAt: BN | null;
-  paidAt: BN | null;
+  completed: boolean;
+  verified: boolean;
+  deliverableHash: Buffer;
+  submittedAt: BN | null;
+  rejectionCode: number | null;
+  verifiedAt: BN | null;
+  verifiedBy: PublicKey | null;
+  approvals: PublicKey[];
+  disputed: boolean;
+  pendingAmendment: MilestoneAmendment | null;
+  bump: number;
+}
+
+export interface MilestoneAmendment {
+  proposer: PublicKey;
+  amount: BN;
+  deadline: BN;
+}
+
+export enum DisputeStatus {
+  Open = 'open',
+  Resolved = 'resolved'
+}
+
+export interface Dispute {
+  escrow: PublicKey;
+  milestone: PublicKey;
+  raisedBy: PublicKey;
+  raisedAt: BN;
+  status: DisputeStatus;
+  creatorEvidence: number[] | null;
+  recipientEvidence: number[] | null;
+  recipientShareBps: number;
+  resolvedAt: BN | null;
 }
 
 export interface Multisig {
   escrow: PublicKey;
+  requester: PublicKey;
   signers: PublicKey[];
-  signatures: boolean[];
+  signedBy: PublicKey[];
   threshold: number;
   createdAt: BN;
+  approvedAt: BN | null;
   executed: boolean;
+  objected: boolean;
+  objectionUpheld: boolean | null;
+  pausedEscrow: boolean;
+}
+
+export interface Config {
+  admin: PublicKey;
+  feeBps: number;
+  treasury: PublicKey;
+  allowedMints: PublicKey[];
+}
+
+export interface Payee {
+  wallet: PublicKey;
+  shareBps: number;
+}
+
+export interface VestingSchedule {
+  amount: BN;
+  startTs: BN;
+  cliffTs: BN;
+  endTs: BN;
+}
+
+export interface InitializeEscrowParams {
+  milestonesCount: number;
+  arbitrator: PublicKey;
+  emergencySigners: PublicKey[];
+  emergencyThreshold: number;
+  emergencyDelay?: number;
+  escrowId?: number | BN;
+  verifiers?: PublicKey[];
+  verifierThreshold?: number;
+  reviewPeriod?: number;
+  gracePeriod?: number;
+  payees?: Payee[];
+  isNative?: boolean;
+  vesting?: VestingSchedule | null;
+  fundingDeadline?: number | null;
+  transferRequiresCreator?: boolean;
+}
+
+export interface Contribution {
+  escrow: PublicKey;
+  funder: PublicKey;
+  amount: BN;
+  refunded: BN;
+  bump: number;
 }
 
 export interface Escrow {
   creator: PublicKey;
+  escrowId: BN;
   recipient: PublicKey;
+  initialRecipient: PublicKey;
+  transferRequiresCreator: boolean;
+  receiptMint: PublicKey | null;
+  payees: Payee[];
+  arbitrator: PublicKey;
+  verifiers: PublicKey[];
+  verifierThreshold: number;
+  reviewPeriod: BN;
+  gracePeriod: BN;
+  emergencySigners: PublicKey[];
+  emergencyThreshold: number;
+  emergencyDelay: BN;
   tokenMint: PublicKey;
-  tokenAccount: PublicKey;
-  totalAmount: BN;
+  isNative: boolean;
+  fundedAmount: BN;
+  amendmentRefunds: BN;
+  fundingStatus: FundingStatus;
+  fundingDeadline: BN | null;
+  crowdfunded: boolean;
   releasedAmount: BN;
+  refundedAmount: BN;
+  refundPool: BN;
+  refundsClaimed: BN;
+  refundedContributions: BN;
   milestonesCount: number;
+  milestonesAdded: number;
+  committedAmount: BN;
+  currentMilestone: number;
+  disputesRaised: number;
+  vesting: VestingSchedule | null;
+  cancelProposedBy: PublicKey | null;
+  cancelRecipientShareBps: number;
   status: EscrowStatus;
   createdAt: BN;
-  updatedAt: BN;
 }
 
 // Escrow Events
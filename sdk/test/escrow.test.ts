@@ -1,10 +1,24 @@
 import { assert } from 'chai';
-import { Keypair, Connection, LAMPORTS_PER_SOL, PublicKey } from '@solana/web3.js';
-import { Token, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair, Connection, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from '@solana/web3.js';
+import {
+  Token,
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  NATIVE_MINT,
+  ExtensionType,
+  getMintLen,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializeMintInstruction,
+  createAssociatedTokenAccount,
+  mintTo,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from '@solana/spl-token';
 import { DapprClient } from '../src';
-import { EscrowStatus, MilestoneStatus } from '../src/types';
+import { EscrowStatus, FundingStatus, InitializeEscrowParams } from '../src/types';
 import { EscrowClient } from '../src/escrow';
-import { Provider, Program, web3, AnchorProvider } from '@project-serum/anchor';
+import { Provider, Program, web3, AnchorProvider, BN } from '@project-serum/anchor';
 import * as anchor from '@project-serum/anchor';
 
 // Test configuration
@@ -20,6 +34,9 @@ const airdropSol = async (connection: Connection, wallet: Keypair, amount = 1) =
   await connection.confirmTransaction(airdropSig);
 };
 
+// Helper function to wait out on-chain time windows
+const sleep = (ms: number) => new Promise(resolve => setTimeout(resolve, ms));
+
 describe('Escrow', () => {
   // Test accounts
   let creator: Keypair;
@@ -27,14 +44,64 @@ describe('Escrow', () => {
   let signer1: Keypair;
   let signer2: Keypair;
   let signer3: Keypair;
+  let arbitrator: Keypair;
+  let treasury: Keypair;
   let dapprClient: DapprClient;
   let escrowClient: EscrowClient;
   let tokenMint: PublicKey;
+  let dapprUsdMint: PublicKey;
   let creatorTokenAccount: PublicKey;
   let recipientTokenAccount: PublicKey;
   let escrow: PublicKey;
   let milestone: PublicKey;
   let multisig: PublicKey;
+  let disputeEscrow: PublicKey;
+
+  // Escrow ids handed out to the suites below; the lifecycle escrow keeps the default id 0
+  let nextEscrowId = 1;
+  const allocateEscrowId = () => nextEscrowId++;
+
+  // Opens an escrow to the recipient under a fresh id, adds its milestones and funds them from the wallet
+  const createEscrowFixture = async ({
+    mint = tokenMint,
+    milestones = [{ amount: 100_000 }],
+    funding = milestones.reduce((total, { amount }) => total + amount, 0),
+    ...params
+  }: Partial<InitializeEscrowParams> & {
+    mint?: PublicKey;
+    milestones?: { amount: number; deadline?: number }[];
+    funding?: number;
+  } = {}) => {
+    const { escrow: created } = await escrowClient.initializeEscrow(
+      recipient.publicKey,
+      mint,
+      {
+        milestonesCount: milestones.length,
+        arbitrator: arbitrator.publicKey,
+        emergencySigners: [signer1.publicKey],
+        emergencyThreshold: 1,
+        escrowId: allocateEscrowId(),
+        ...params,
+      }
+    );
+
+    const defaultDeadline = Math.floor(Date.now() / 1000) + 7 * 24 * 60 * 60;
+    const milestonePDAs: PublicKey[] = [];
+    for (let i = 0; i < milestones.length; i++) {
+      const { amount, deadline = defaultDeadline } = milestones[i];
+      const { milestone: milestonePDA } = await escrowClient.addMilestone(created, i, amount, deadline);
+      milestonePDAs.push(milestonePDA);
+    }
+
+    if (funding > 0) {
+      const tokenClient = new Token(provider.connection, mint, TOKEN_PROGRAM_ID, creator);
+      const walletTokenAccount = await tokenClient.getOrCreateAssociatedAccountInfo(provider.wallet.publicKey);
+      await tokenClient.mintTo(walletTokenAccount.address, creator.publicKey, [], funding);
+      await escrowClient.fundEscrow(created, mint, funding);
+    }
+
+    return { escrow: created, milestones: milestonePDAs };
+  };
 
   before(async () => {
     // Generate keypairs for test accounts
@@ -43,6 +110,8 @@ describe('Escrow', () => {
     signer1 = Keypair.generate();
     signer2 = Keypair.generate();
     signer3 = Keypair.generate();
+    arbitrator = Keypair.generate();
+    treasury = Keypair.generate();
 
     // Airdrop SOL to creator for transaction fees
     await airdropSol(provider.connection, creator, 10);
@@ -65,6 +134,7 @@ describe('Escrow', () => {
     // Create token accounts
     creatorTokenAccount = await token.createAssociatedTokenAccount(creator.publicKey);
     recipientTokenAccount = await token.createAssociatedTokenAccount(recipient.publicKey);
+    await token.createAssociatedTokenAccount(treasury.publicKey);
 
     // Mint some test tokens to the creator
     await token.mintTo(creatorTokenAccount, creator.publicKey, [], 1_000_000_000); // 1000 tokens with 6 decimals
@@ -72,6 +142,13 @@ describe('Escrow', () => {
     // Initialize Dappr client
     dapprClient = new DapprClient(provider);
     escrowClient = dapprClient.escrow;
+
+    // The config starts its allow-list with the DAPPR_USD mint, created at its PDA by the program
+    ({ dapprUsdMint } = await dapprClient.initializeTokens());
+
+    // Fee-free to begin with so payouts below are exact
+    await escrowClient.initializeConfig(0, treasury.publicKey);
+    await escrowClient.addAllowedMint(tokenMint);
   });
 
   describe('Escrow Lifecycle', () => {
@@ -79,7 +156,13 @@ describe('Escrow', () => {
       // Initialize escrow
       const { escrow: escrowPubkey, tx } = await escrowClient.initializeEscrow(
         recipient.publicKey,
-        3 // 3 milestones
+        tokenMint,
+        {
+          milestonesCount: 3,
+          arbitrator: arbitrator.publicKey,
+          emergencySigners: [signer1.publicKey, signer2.publicKey, signer3.publicKey],
+          emergencyThreshold: 2,
+        }
       );
       
       escrow = escrowPubkey;
@@ -89,9 +172,13 @@ describe('Escrow', () => {
       
       assert.ok(escrowAccount.creator.equals(creator.publicKey), 'Creator should be set');
       assert.ok(escrowAccount.recipient.equals(recipient.publicKey), 'Recipient should be set');
+      assert.ok(escrowAccount.arbitrator.equals(arbitrator.publicKey), 'Arbitrator should be set');
       assert.equal(escrowAccount.milestonesCount, 3, 'Should have 3 milestones');
-      assert.equal(escrowAccount.status, EscrowStatus.Initialized, 'Status should be Initialized');
-      assert.equal(escrowAccount.totalAmount.toNumber(), 0, 'Initial total amount should be 0');
+      assert.equal(escrowAccount.emergencySigners.length, 3, 'Should have 3 emergency signers');
+      assert.equal(escrowAccount.emergencyThreshold, 2, 'Emergency threshold should be 2');
+      assert.equal(escrowAccount.status, EscrowStatus.Draft, 'Status should be Draft');
+      assert.equal(escrowAccount.fundedAmount.toNumber(), 0, 'Initial funded amount should be 0');
+      assert.equal(escrowAccount.fundingStatus, FundingStatus.Unfunded, 'Funding status should be Unfunded');
     });
 
     it('should add milestones to the escrow', async () => {
@@ -119,7 +206,8 @@ describe('Escrow', () => {
         assert.equal(milestoneAccount.index, i, `Milestone ${i} index should match`);
         assert.equal(milestoneAccount.amount.toNumber(), amount, `Milestone ${i} amount should match`);
         assert.equal(milestoneAccount.deadline.toNumber(), deadline, `Milestone ${i} deadline should match`);
-        assert.equal(milestoneAccount.status, MilestoneStatus.Pending, `Milestone ${i} status should be Pending`);
+        assert.isFalse(milestoneAccount.completed, `Milestone ${i} should not be submitted yet`);
+        assert.isFalse(milestoneAccount.verified, `Milestone ${i} should not be verified yet`);
       }
     });
 
@@ -142,8 +230,10 @@ describe('Escrow', () => {
       
       // Verify escrow was funded
       const escrowAccount = await escrowClient.getEscrow(escrow);
-      assert.equal(escrowAccount.status, EscrowStatus.Funded, 'Status should be Funded');
-      assert.equal(escrowAccount.totalAmount.toNumber(), 600_000, 'Total amount should be 0.6 tokens');
+      assert.equal(escrowAccount.status, EscrowStatus.InProgress, 'Status should be InProgress');
+      assert.equal(escrowAccount.fundedAmount.toNumber(), 600_000, 'Funded amount should be 0.6 tokens');
+      assert.equal(escrowAccount.committedAmount.toNumber(), 600_000, 'Committed amount should be 0.6 tokens');
+      assert.equal(escrowAccount.fundingStatus, FundingStatus.FullyFunded, 'Funding status should be FullyFunded');
       
       // Verify tokens were transferred to escrow
       const tokenClient = new Token(
@@ -157,16 +247,53 @@ describe('Escrow', () => {
       assert.equal(escrowTokenBalance.amount.toNumber(), 600_000, 'Escrow should have 0.6 tokens');
     });
 
-    it('should complete a milestone', async () => {
-      // Complete the first milestone
-      const tx = await escrowClient.completeMilestone(escrow, 0);
+    it('should record a milestone submission from the recipient', async () => {
+      const recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      const deliverableHash = Buffer.from('bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi');
+      
+      await recipientClient.escrow.submitMilestone(escrow, 0, deliverableHash);
+      
+      const [milestonePubkey] = await escrowClient.getMilestonePDA(escrow, 0);
+      const milestoneAccount = await escrowClient.getMilestone(milestonePubkey);
+      assert.ok(Buffer.from(milestoneAccount.deliverableHash).equals(deliverableHash), 'Deliverable hash should be recorded');
+      assert.ok(milestoneAccount.submittedAt, 'Submitted at should be set');
+    });
+
+    it('should reject milestone approval from an unrelated signer', async () => {
+      const outsider = Keypair.generate();
+      await airdropSol(provider.connection, outsider, 1);
+      
+      const outsiderClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(outsider), {})
+      );
+      
+      try {
+        await outsiderClient.escrow.approveMilestone(escrow, 0);
+        assert.fail('Approval by an unrelated signer should fail');
+      } catch (error) {
+        assert.include(String(error), 'UnauthorizedVerifier');
+      }
+      
+      // Verify the milestone was left untouched
+      const [milestonePubkey] = await escrowClient.getMilestonePDA(escrow, 0);
+      const milestoneAccount = await escrowClient.getMilestone(milestonePubkey);
+      assert.isFalse(milestoneAccount.verified, 'Milestone should not be approved');
+      assert.isEmpty(milestoneAccount.approvals, 'No approval should be recorded');
+    });
+
+    it('should approve a submitted milestone', async () => {
+      // Approve the first milestone
+      const tx = await escrowClient.approveMilestone(escrow, 0);
       
       // Verify milestone was marked as completed
       const [milestonePubkey] = await escrowClient.getMilestonePDA(escrow, 0);
       const milestoneAccount = await escrowClient.getMilestone(milestonePubkey);
       
-      assert.equal(milestoneAccount.status, MilestoneStatus.Completed, 'Milestone status should be Completed');
-      assert.ok(milestoneAccount.completedAt, 'Completed at should be set');
+      assert.isTrue(milestoneAccount.verified, 'Milestone should be verified');
+      assert.ok(milestoneAccount.verifiedAt, 'Verified at should be set');
+      assert.ok(milestoneAccount.verifiedBy!.equals(provider.wallet.publicKey), 'Verified by should be the approver');
     });
 
     it('should release funds for a completed milestone', async () => {
@@ -196,24 +323,21 @@ describe('Escrow', () => {
         'Recipient should have received 0.1 tokens'
       );
       
-      // Verify milestone was marked as paid
-      const [milestonePubkey] = await escrowClient.getMilestonePDA(escrow, 0);
-      const milestoneAccount = await escrowClient.getMilestone(milestonePubkey);
-      assert.equal(milestoneAccount.status, MilestoneStatus.Paid, 'Milestone status should be Paid');
-      assert.ok(milestoneAccount.paidAt, 'Paid at should be set');
-      
-      // Verify released amount was updated in escrow
+      // Verify released amount was updated in escrow and the next milestone is current
       const escrowAccount = await escrowClient.getEscrow(escrow);
+      assert.equal(escrowAccount.currentMilestone, 1, 'The next milestone should be current');
       assert.equal(escrowAccount.releasedAmount.toNumber(), 100_000, 'Released amount should be 0.1 tokens');
     });
 
     it('should initiate emergency withdrawal', async () => {
-      // Initiate emergency withdrawal with 2-of-3 multisig
+      // An emergency signer opens the request against the 2-of-3 multisig configured at creation
       const signers = [signer1.publicKey, signer2.publicKey, signer3.publicKey];
-      const { multisig: multisigPubkey } = await escrowClient.initiateEmergencyWithdrawal(
+      const signer1Client = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer1), {})
+      );
+      const { multisig: multisigPubkey } = await signer1Client.escrow.initiateEmergencyWithdrawal(
         escrow,
-        creator.publicKey,
-        signers
+        creator.publicKey
       );
       
       multisig = multisigPubkey;
@@ -221,6 +345,7 @@ describe('Escrow', () => {
       // Verify multisig was created
       const multisigAccount = await escrowClient.getMultisig(multisig);
       assert.ok(multisigAccount.escrow.equals(escrow), 'Escrow should match');
+      assert.ok(multisigAccount.requester.equals(signer1.publicKey), 'Requester should be recorded');
       assert.equal(multisigAccount.signers.length, 3, 'Should have 3 signers');
       assert.equal(multisigAccount.threshold, 2, 'Threshold should be 2');
       assert.deepEqual(
@@ -229,53 +354,1727 @@ describe('Escrow', () => {
         'Signers should match'
       );
       assert.deepEqual(
-        multisigAccount.signatures,
-        [false, false, false],
-        'Initial signatures should all be false'
+        multisigAccount.signedBy.map(s => s.toString()),
+        [signer1.publicKey.toString()],
+        'Opening the request should count as the requester\'s signature'
       );
+      assert.isNull(multisigAccount.approvedAt, 'One signature should not reach the threshold');
     });
 
     it('should sign emergency withdrawal', async () => {
-      // Sign with the first signer
-      const tx1 = await escrowClient.signEmergencyWithdrawal(escrow);
+      // Anyone outside the signer set is turned away
+      try {
+        await escrowClient.signEmergencyWithdrawal(escrow);
+        assert.fail('Only emergency signers should be able to sign');
+      } catch (error) {
+        assert.include(String(error), 'InvalidSigner');
+      }
+      
+      // The second signer brings the request to its threshold
+      const signer2Client = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer2), {})
+      );
+      await signer2Client.escrow.signEmergencyWithdrawal(escrow);
       
-      // Verify signature was recorded
-      const multisigAccount1 = await escrowClient.getMultisig(multisig);
+      const multisigAccount = await escrowClient.getMultisig(multisig);
       assert.deepEqual(
-        multisigAccount1.signatures,
-        [true, false, false],
-        'First signature should be recorded'
+        multisigAccount.signedBy.map(s => s.toString()),
+        [signer1.publicKey.toString(), signer2.publicKey.toString()],
+        'Second signature should be recorded'
+      );
+      assert.isTrue(
+        multisigAccount.signedBy.length >= multisigAccount.threshold,
+        'Threshold should be reached'
+      );
+      assert.ok(multisigAccount.approvedAt, 'Approved at should be set');
+      assert.isFalse(multisigAccount.executed, 'Withdrawal should wait out the objection window');
+    });
+
+    it('should not execute an emergency withdrawal during the objection window', async () => {
+      try {
+        await escrowClient.executeEmergencyWithdrawal(escrow);
+        assert.fail('Execution before the delay should fail');
+      } catch (error) {
+        assert.include(String(error), 'EmergencyDelayActive');
+      }
+      
+      const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        TOKEN_PROGRAM_ID,
+        tokenMint,
+        escrow,
+        true
+      );
+      const tokenClient = new Token(
+        provider.connection,
+        tokenMint,
+        TOKEN_PROGRAM_ID,
+        creator
+      );
+      const escrowTokenBalance = await tokenClient.getAccountInfo(escrowTokenAccount);
+      assert.equal(escrowTokenBalance.amount.toNumber(), 500_000, 'Escrow should still hold the unreleased 0.5 tokens');
+    });
+
+    it('should put the escrow into dispute when the recipient objects', async () => {
+      const recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      
+      await recipientClient.escrow.objectEmergencyWithdrawal(escrow);
+      
+      const multisigAccount = await escrowClient.getMultisig(multisig);
+      assert.isTrue(multisigAccount.objected, 'Request should be marked as objected');
+      assert.isNull(multisigAccount.objectionUpheld, 'The objection should await a ruling');
+      const escrowAccount = await escrowClient.getEscrow(escrow);
+      assert.equal(escrowAccount.status, EscrowStatus.Disputed, 'The objection should dispute the escrow');
+      
+      try {
+        await escrowClient.executeEmergencyWithdrawal(escrow);
+        assert.fail('An objected request should wait for the arbitrator');
+      } catch (error) {
+        assert.include(String(error), 'EmergencyObjectionPending');
+      }
+    });
+
+    it('should refuse the withdrawal once the arbitrator upholds the objection', async () => {
+      await airdropSol(provider.connection, arbitrator, 1);
+      const arbitratorClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(arbitrator), {})
       );
+      await arbitratorClient.escrow.resolveEmergencyObjection(escrow, true);
+      
+      const escrowAccount = await escrowClient.getEscrow(escrow);
+      assert.equal(escrowAccount.status, EscrowStatus.InProgress, 'The ruling should end the dispute');
       
-      // Sign with the second signer (using a different wallet)
-      const originalSigner = provider.wallet;
       try {
-        // Switch to signer2
-        provider.wallet = {
-          publicKey: signer2.publicKey,
-          signTransaction: signer2.signTransaction,
-          signAllTransactions: signer2.signAllTransactions,
-        };
-        
-        const tx2 = await escrowClient.signEmergencyWithdrawal(escrow);
-        
-        // Verify second signature was recorded
-        const multisigAccount2 = await escrowClient.getMultisig(multisig);
-        assert.deepEqual(
-          multisigAccount2.signatures,
-          [true, true, false],
-          'Second signature should be recorded'
+        await escrowClient.executeEmergencyWithdrawal(escrow);
+        assert.fail('A refused request should never execute');
+      } catch (error) {
+        assert.include(String(error), 'EmergencyRequestObjected');
+      }
+      
+      // Anyone can clear the refused request to make way for a new one
+      const outsiderClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer3), {})
+      );
+      await outsiderClient.escrow.cancelEmergencyWithdrawal(escrow);
+      
+      const multisigInfo = await provider.connection.getAccountInfo(multisig);
+      assert.isNull(multisigInfo, 'Refused request should be closed');
+    });
+  });
+
+  describe('Milestone Registry', () => {
+    let registryEscrow: PublicKey;
+    const deadline = Math.floor(Date.now() / 1000) + 7 * 24 * 60 * 60;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      // A second escrow with the same recipient, kept apart by its escrow id
+      ({ escrow: registryEscrow } = await createEscrowFixture({ milestonesCount: 2, milestones: [] }));
+    });
+
+    it('should assign sequential indices matching the milestone PDAs', async () => {
+      for (let i = 0; i < 2; i++) {
+        const { milestone: milestonePubkey } = await escrowClient.addMilestone(
+          registryEscrow,
+          i,
+          50_000,
+          deadline
         );
         
-        // Verify threshold was reached
-        assert.isTrue(
-          multisigAccount2.signatures.filter(Boolean).length >= multisigAccount2.threshold,
-          'Threshold should be reached'
-        );
-      } finally {
-        // Restore original wallet
-        provider.wallet = originalSigner;
+        const [expectedPubkey] = await escrowClient.getMilestonePDA(registryEscrow, i);
+        assert.ok(milestonePubkey.equals(expectedPubkey), `Milestone ${i} PDA should match its index`);
+        
+        const milestoneAccount = await escrowClient.getMilestone(milestonePubkey);
+        assert.equal(milestoneAccount.index, i, `Milestone ${i} index should match its PDA seed`);
+      }
+      
+      const escrowAccount = await escrowClient.getEscrow(registryEscrow);
+      assert.equal(escrowAccount.milestonesAdded, 2, 'Should have registered 2 milestones');
+      assert.equal(escrowAccount.committedAmount.toNumber(), 100_000, 'Committed amount should be the milestone sum');
+      assert.equal(escrowAccount.status, EscrowStatus.AwaitingFunding, 'Status should be AwaitingFunding');
+    });
+
+    it('should reject submissions before the escrow is funded', async () => {
+      const recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      
+      try {
+        await recipientClient.escrow.submitMilestone(registryEscrow, 0, Buffer.from('early-deliverable'));
+        assert.fail('Submitting before funding should fail');
+      } catch (error) {
+        assert.include(String(error), 'EscrowNotFullyFunded');
+      }
+    });
+
+    it('should reject adding more milestones than declared', async () => {
+      try {
+        await escrowClient.addMilestone(registryEscrow, 2, 50_000, deadline);
+        assert.fail('Adding a milestone beyond milestonesCount should fail');
+      } catch (error) {
+        assert.include(String(error), 'MilestoneLimitReached');
+      }
+    });
+
+    it('should reject adding milestones after funding has started', async () => {
+      const { escrow: fundedEscrow } = await createEscrowFixture({
+        milestonesCount: 2,
+        milestones: [{ amount: 50_000, deadline }],
+      });
+      
+      try {
+        await escrowClient.addMilestone(fundedEscrow, 1, 50_000, deadline);
+        assert.fail('Adding a milestone after funding should fail');
+      } catch (error) {
+        assert.include(String(error), 'FundingAlreadyStarted');
+      }
+    });
+
+    it('should reject empty deposits', async () => {
+      try {
+        await escrowClient.fundEscrow(registryEscrow, tokenMint, 0);
+        assert.fail('A zero deposit should fail');
+      } catch (error) {
+        assert.include(String(error), 'InvalidDepositAmount');
+      }
+    });
+
+    it('should reject deposits above the committed total', async () => {
+      const tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      const walletTokenAccount = await tokenClient.getOrCreateAssociatedAccountInfo(provider.wallet.publicKey);
+      await tokenClient.mintTo(walletTokenAccount.address, creator.publicKey, [], 100_001);
+      
+      try {
+        await escrowClient.fundEscrow(registryEscrow, tokenMint, 100_001);
+        assert.fail('Funding past the milestone total should fail');
+      } catch (error) {
+        assert.include(String(error), 'FundingExceedsCommitment');
+      }
+    });
+
+    it('should only let the creator close milestone registration by funding', async () => {
+      const { escrow: draftEscrow } = await createEscrowFixture({
+        milestonesCount: 2,
+        milestones: [{ amount: 50_000, deadline }],
+        funding: 0,
+      });
+      
+      const tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      const outsiderTokenAccount = await tokenClient.getOrCreateAssociatedAccountInfo(signer2.publicKey);
+      await tokenClient.mintTo(outsiderTokenAccount.address, creator.publicKey, [], 10_000);
+      const outsiderClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer2), {})
+      );
+      
+      try {
+        await outsiderClient.escrow.fundEscrow(draftEscrow, tokenMint, 10_000);
+        assert.fail('An outsider deposit should not close a draft');
+      } catch (error) {
+        assert.include(String(error), 'EscrowNotFinalized');
+      }
+      
+      const escrowAccount = await escrowClient.getEscrow(draftEscrow);
+      assert.equal(escrowAccount.status, EscrowStatus.Draft, 'The escrow should still be a draft');
+      assert.equal(escrowAccount.milestonesAdded, 1, 'The schedule should stay open');
+    });
+  });
+
+  describe('Emergency Signers', () => {
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+    });
+
+    it('should reject a threshold above the signer count', async () => {
+      try {
+        await createEscrowFixture({
+          emergencySigners: [signer1.publicKey, signer2.publicKey],
+          emergencyThreshold: 3,
+        });
+        assert.fail('A 3-of-2 emergency multisig should be rejected');
+      } catch (error) {
+        assert.include(String(error), 'InvalidMultisigThreshold');
+      }
+    });
+
+    it('should reject duplicate emergency signers', async () => {
+      try {
+        await createEscrowFixture({
+          emergencySigners: [signer1.publicKey, signer1.publicKey, signer2.publicKey],
+          emergencyThreshold: 2,
+        });
+        assert.fail('Duplicate emergency signers should be rejected');
+      } catch (error) {
+        assert.include(String(error), 'InvalidEmergencySigners');
+      }
+    });
+  });
+
+  describe('Emergency Withdrawal Requests', () => {
+    let guardedEscrow: PublicKey;
+    let guardedMultisig: PublicKey;
+    let outsider: Keypair;
+    let signer1Client: DapprClient;
+    let outsiderClient: DapprClient;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      outsider = Keypair.generate();
+      await airdropSol(provider.connection, outsider, 1);
+      
+      signer1Client = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer1), {})
+      );
+      outsiderClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(outsider), {})
+      );
+      
+      ({ escrow: guardedEscrow } = await createEscrowFixture({
+        emergencySigners: [signer1.publicKey, signer2.publicKey, signer3.publicKey],
+        emergencyThreshold: 2,
+        funding: 0,
+      }));
+      [guardedMultisig] = await escrowClient.getMultisigPDA(guardedEscrow);
+    });
+
+    it('should reject an emergency request from an outsider', async () => {
+      try {
+        await outsiderClient.escrow.initiateEmergencyWithdrawal(guardedEscrow, provider.wallet.publicKey);
+        assert.fail('An outsider should not be able to open an emergency request');
+      } catch (error) {
+        assert.include(String(error), 'InvalidSigner');
+      }
+    });
+
+    it('should not let a pending request be replaced', async () => {
+      await signer1Client.escrow.initiateEmergencyWithdrawal(guardedEscrow, provider.wallet.publicKey);
+      
+      try {
+        await outsiderClient.escrow.initiateEmergencyWithdrawal(guardedEscrow, provider.wallet.publicKey);
+        assert.fail('Re-initiating a pending request should fail');
+      } catch (error) {
+        assert.include(String(error), 'already in use');
+      }
+      
+      const multisigAccount = await escrowClient.getMultisig(guardedMultisig);
+      assert.ok(multisigAccount.requester.equals(signer1.publicKey), 'Original request should be untouched');
+      assert.deepEqual(
+        multisigAccount.signers.map(s => s.toString()),
+        [signer1.publicKey, signer2.publicKey, signer3.publicKey].map(s => s.toString()),
+        'Signer set should still be the one fixed at creation'
+      );
+    });
+
+    it('should reject cancellation of a live request by anyone but the requester', async () => {
+      try {
+        await outsiderClient.escrow.cancelEmergencyWithdrawal(guardedEscrow);
+        assert.fail('An outsider should not be able to cancel a live request');
+      } catch (error) {
+        assert.include(String(error), 'EmergencyRequestActive');
+      }
+    });
+
+    it('should let the requester cancel and a new request be opened', async () => {
+      await signer1Client.escrow.cancelEmergencyWithdrawal(guardedEscrow);
+      
+      const closedAccount = await provider.connection.getAccountInfo(guardedMultisig);
+      assert.isNull(closedAccount, 'Multisig account should be closed');
+      
+      const signer2Client = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer2), {})
+      );
+      await signer2Client.escrow.initiateEmergencyWithdrawal(guardedEscrow, provider.wallet.publicKey);
+      
+      const multisigAccount = await escrowClient.getMultisig(guardedMultisig);
+      assert.ok(multisigAccount.requester.equals(signer2.publicKey), 'New request should belong to signer2');
+    });
+  });
+
+  describe('Payee Splits', () => {
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+    });
+
+    it('should split a release between payees and give the dust to the first', async () => {
+      const payees = [
+        { wallet: recipient.publicKey, shareBps: 6_000 },
+        { wallet: signer1.publicKey, shareBps: 3_000 },
+        { wallet: signer2.publicKey, shareBps: 1_000 },
+      ];
+      
+      const { escrow: splitEscrow } = await createEscrowFixture({ milestones: [{ amount: 100_001 }], payees });
+      
+      const tokenClient = new Token(
+        provider.connection,
+        tokenMint,
+        TOKEN_PROGRAM_ID,
+        creator
+      );
+      
+      const recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      await recipientClient.escrow.submitMilestone(splitEscrow, 0, Buffer.from('split-deliverable'));
+      await escrowClient.approveMilestone(splitEscrow, 0);
+      
+      const payeeTokenAccounts = await Promise.all(
+        payees.map(payee => tokenClient.getOrCreateAssociatedAccountInfo(payee.wallet))
+      );
+      const balancesBefore = payeeTokenAccounts.map(account => account.amount.toNumber());
+      
+      await escrowClient.releaseFunds(splitEscrow, 0, tokenMint, recipient.publicKey);
+      
+      const balancesAfter = await Promise.all(
+        payeeTokenAccounts.map(async account => (await tokenClient.getAccountInfo(account.address)).amount.toNumber())
+      );
+      assert.deepEqual(
+        balancesAfter.map((balance, i) => balance - balancesBefore[i]),
+        [60_001, 30_000, 10_000],
+        'Payees should receive their shares with the dust going to the first'
+      );
+    });
+
+    it('should reject payee shares that do not sum to 100%', async () => {
+      try {
+        await createEscrowFixture({
+          payees: [
+            { wallet: recipient.publicKey, shareBps: 5_000 },
+            { wallet: signer1.publicKey, shareBps: 4_000 },
+          ],
+        });
+        assert.fail('Shares summing to 90% should be rejected');
+      } catch (error) {
+        assert.include(String(error), 'InvalidPayees');
+      }
+    });
+  });
+
+  describe('Protocol Fee', () => {
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+    });
+
+    it('should reject config updates from anyone but the admin', async () => {
+      const outsiderClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer3), {})
+      );
+      
+      try {
+        await outsiderClient.escrow.updateConfig(1_000, signer3.publicKey);
+        assert.fail('A non-admin config update should fail');
+      } catch (error) {
+        assert.include(String(error), 'UnauthorizedAdmin');
+      }
+    });
+
+    it('should route the fee to the treasury on release', async () => {
+      await escrowClient.updateConfig(250, treasury.publicKey); // 2.5%
+      
+      const config = await escrowClient.getConfig();
+      assert.equal(config.feeBps, 250, 'Fee should be updated');
+      
+      const { escrow: feeEscrow } = await createEscrowFixture({ milestones: [{ amount: 200_000 }] });
+      
+      const tokenClient = new Token(
+        provider.connection,
+        tokenMint,
+        TOKEN_PROGRAM_ID,
+        creator
+      );
+      
+      const recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      await recipientClient.escrow.submitMilestone(feeEscrow, 0, Buffer.from('fee-deliverable'));
+      await escrowClient.approveMilestone(feeEscrow, 0);
+      
+      const treasuryTokenAccount = await Token.getAssociatedTokenAddress(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        TOKEN_PROGRAM_ID,
+        tokenMint,
+        treasury.publicKey
+      );
+      const treasuryBefore = (await tokenClient.getAccountInfo(treasuryTokenAccount)).amount.toNumber();
+      const recipientBefore = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      
+      await escrowClient.releaseFunds(feeEscrow, 0, tokenMint, recipient.publicKey);
+      
+      const treasuryAfter = (await tokenClient.getAccountInfo(treasuryTokenAccount)).amount.toNumber();
+      const recipientAfter = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      assert.equal(treasuryAfter - treasuryBefore, 5_000, 'Treasury should receive 2.5%');
+      assert.equal(recipientAfter - recipientBefore, 195_000, 'Recipient should receive the remainder');
+    });
+  });
+
+  describe('Mint Allow-List', () => {
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+    });
+
+    it('should start the allow-list with the DAPPR_USD mint', async () => {
+      const config = await escrowClient.getConfig();
+      assert.equal(config.allowedMints.length, 2, 'DAPPR_USD and the test mint should be allowed');
+      assert.isTrue(config.allowedMints[0].equals(dapprUsdMint), 'DAPPR_USD should be allowed');
+      assert.isTrue(config.allowedMints[1].equals(tokenMint), 'The test mint should be allowed');
+    });
+
+    it('should reject allow-list changes from anyone but the admin', async () => {
+      const outsiderClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer3), {})
+      );
+      
+      try {
+        await outsiderClient.escrow.addAllowedMint(NATIVE_MINT);
+        assert.fail('A non-admin allow-list change should fail');
+      } catch (error) {
+        assert.include(String(error), 'UnauthorizedAdmin');
+      }
+    });
+
+    it('should reject escrows in a mint that is not allowed', async () => {
+      try {
+        await createEscrowFixture({ mint: NATIVE_MINT, isNative: true, funding: 0 });
+        assert.fail('Escrow in an unlisted mint should fail');
+      } catch (error) {
+        assert.include(String(error), 'MintNotAllowed');
+      }
+    });
+
+    it('should let the admin allow and remove mints', async () => {
+      await escrowClient.addAllowedMint(NATIVE_MINT);
+      
+      try {
+        await escrowClient.addAllowedMint(NATIVE_MINT);
+        assert.fail('Adding a mint twice should fail');
+      } catch (error) {
+        assert.include(String(error), 'InvalidAllowedMints');
       }
+      
+      const throwawayMint = Keypair.generate().publicKey;
+      await escrowClient.addAllowedMint(throwawayMint);
+      await escrowClient.removeAllowedMint(throwawayMint);
+      
+      const config = await escrowClient.getConfig();
+      assert.equal(config.allowedMints.length, 3, 'DAPPR_USD, the test mint and SOL should be allowed');
+      assert.isTrue(config.allowedMints[2].equals(NATIVE_MINT), 'SOL should be allowed');
+    });
+  });
+
+  describe('Native SOL', () => {
+    let nativeEscrow: PublicKey;
+    let rentReserve: number;
+
+    before(async () => {
+      await escrowClient.updateConfig(250, treasury.publicKey); // 2.5%
+      
+      ({ escrow: nativeEscrow } = await createEscrowFixture({
+        mint: NATIVE_MINT,
+        isNative: true,
+        milestones: [{ amount: LAMPORTS_PER_SOL }],
+        funding: 0,
+      }));
+      rentReserve = await provider.connection.getBalance(nativeEscrow);
+    });
+
+    it('should hold deposited lamports in the escrow account', async () => {
+      await escrowClient.fundEscrow(nativeEscrow, NATIVE_MINT, LAMPORTS_PER_SOL);
+      
+      const escrowAccount = await escrowClient.getEscrow(nativeEscrow);
+      assert.isTrue(escrowAccount.isNative, 'Escrow should be native');
+      assert.isTrue(escrowAccount.tokenMint.equals(NATIVE_MINT), 'Escrow should record the native mint');
+      assert.equal(escrowAccount.status, EscrowStatus.InProgress, 'Escrow should be in progress');
+      
+      const balance = await provider.connection.getBalance(nativeEscrow);
+      assert.equal(balance - rentReserve, LAMPORTS_PER_SOL, 'Escrow should hold the deposit on top of its rent');
+    });
+
+    it('should release lamports to the recipient and the fee to the treasury', async () => {
+      const recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      await recipientClient.escrow.submitMilestone(nativeEscrow, 0, Buffer.from('native-deliverable'));
+      await escrowClient.approveMilestone(nativeEscrow, 0);
+      
+      const treasuryBefore = await provider.connection.getBalance(treasury.publicKey);
+      const recipientBefore = await provider.connection.getBalance(recipient.publicKey);
+      
+      await escrowClient.releaseFunds(nativeEscrow, 0, NATIVE_MINT, recipient.publicKey);
+      
+      const treasuryAfter = await provider.connection.getBalance(treasury.publicKey);
+      const recipientAfter = await provider.connection.getBalance(recipient.publicKey);
+      assert.equal(treasuryAfter - treasuryBefore, LAMPORTS_PER_SOL * 0.025, 'Treasury should receive 2.5%');
+      assert.equal(recipientAfter - recipientBefore, LAMPORTS_PER_SOL * 0.975, 'Recipient should receive the remainder');
+      
+      const balance = await provider.connection.getBalance(nativeEscrow);
+      assert.equal(balance, rentReserve, 'Escrow should keep only its rent reserve');
+    });
+
+    it('should reject payee wallets that are not rent-exempt', async () => {
+      const emptyWallet = Keypair.generate().publicKey;
+      try {
+        await createEscrowFixture({
+          mint: NATIVE_MINT,
+          isNative: true,
+          payees: [{ wallet: emptyWallet, shareBps: 10_000 }],
+          funding: 0,
+        });
+        assert.fail('An unfunded payee wallet could never receive a small share');
+      } catch (error) {
+        assert.include(String(error), 'PayoutBelowRentExemption');
+      }
+    });
+  });
+
+  describe('Token-2022', () => {
+    let feeMint: PublicKey;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      // Token-2022 mint withholding 1% of every transfer
+      const mintKeypair = Keypair.generate();
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      const lamports = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+      const createMintTx = new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: creator.publicKey,
+          newAccountPubkey: mintKeypair.publicKey,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          mintKeypair.publicKey,
+          creator.publicKey,
+          creator.publicKey,
+          100, // 1%
+          BigInt(1_000_000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mintKeypair.publicKey, 6, creator.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      );
+      await sendAndConfirmTransaction(provider.connection, createMintTx, [creator, mintKeypair]);
+      feeMint = mintKeypair.publicKey;
+      await escrowClient.addAllowedMint(feeMint);
+      
+      const walletTokenAccount = await createAssociatedTokenAccount(
+        provider.connection,
+        creator,
+        feeMint,
+        provider.wallet.publicKey,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        provider.connection,
+        creator,
+        feeMint,
+        walletTokenAccount,
+        creator,
+        1_000_000,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+    });
+
+    it('should resolve the token program from the mint', async () => {
+      const legacyProgram = await escrowClient.getTokenProgram(tokenMint);
+      const feeMintProgram = await escrowClient.getTokenProgram(feeMint);
+      
+      assert.isTrue(legacyProgram.equals(TOKEN_PROGRAM_ID), 'Legacy mint should use the Token program');
+      assert.isTrue(feeMintProgram.equals(TOKEN_2022_PROGRAM_ID), 'Fee mint should use Token-2022');
+    });
+
+    it('should only count the amount received from a transfer-fee mint', async () => {
+      const { escrow: feeEscrow } = await createEscrowFixture({ mint: feeMint, funding: 0 });
+      await escrowClient.fundEscrow(feeEscrow, feeMint, 50_000);
+      
+      const escrowAccount = await escrowClient.getEscrow(feeEscrow);
+      assert.isTrue(escrowAccount.tokenMint.equals(feeMint), 'Escrow should be denominated in the fee mint');
+      assert.equal(escrowAccount.fundedAmount.toNumber(), 49_500, 'Funded amount should exclude the withheld fee');
+      assert.equal(escrowAccount.fundingStatus, FundingStatus.PartiallyFunded, 'Escrow should still need funding');
+      
+      const escrowTokenAccount = await getAccount(
+        provider.connection,
+        getAssociatedTokenAddressSync(feeMint, feeEscrow, true, TOKEN_2022_PROGRAM_ID),
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(Number(escrowTokenAccount.amount), 49_500, 'Escrow should hold the net deposit');
+    });
+  });
+
+  describe('Linear Vesting', () => {
+    let vestingEscrow: PublicKey;
+    let tokenClient: Token;
+    let recipientClient: DapprClient;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      
+      // Started 100 seconds ago and ends 100 seconds from now, so about half is vested
+      const now = Math.floor(Date.now() / 1000);
+      ({ escrow: vestingEscrow } = await createEscrowFixture({
+        milestones: [],
+        funding: 100_000,
+        vesting: {
+          amount: new BN(100_000),
+          startTs: new BN(now - 100),
+          cliffTs: new BN(now - 100),
+          endTs: new BN(now + 100),
+        },
+      }));
+    });
+
+    it('should reject a schedule that ends before it starts', async () => {
+      const now = Math.floor(Date.now() / 1000);
+      
+      try {
+        await createEscrowFixture({
+          milestones: [],
+          funding: 0,
+          vesting: {
+            amount: new BN(100_000),
+            startTs: new BN(now + 100),
+            cliffTs: new BN(now + 100),
+            endTs: new BN(now),
+          },
+        });
+        assert.fail('An inverted vesting schedule should fail');
+      } catch (error) {
+        assert.include(String(error), 'InvalidVestingSchedule');
+      }
+    });
+
+    it('should let the recipient withdraw what has vested', async () => {
+      const escrowAccount = await escrowClient.getEscrow(vestingEscrow);
+      assert.equal(escrowAccount.status, EscrowStatus.InProgress, 'Funded vesting escrow should be in progress');
+      
+      const recipientBefore = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      await recipientClient.escrow.withdrawVested(vestingEscrow);
+      const recipientAfter = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      
+      const withdrawn = recipientAfter - recipientBefore;
+      assert.isAbove(withdrawn, 0, 'Something should have vested');
+      assert.isBelow(withdrawn, 100_000, 'Not everything should have vested');
+      
+      const updated = await escrowClient.getEscrow(vestingEscrow);
+      assert.equal(updated.releasedAmount.toNumber(), withdrawn, 'Released amount should track withdrawals');
+    });
+
+    it('should pay out the vested part and refund the rest when the creator cancels', async () => {
+      await escrowClient.cancelVesting(vestingEscrow);
+      
+      const escrowAccount = await escrowClient.getEscrow(vestingEscrow);
+      assert.equal(escrowAccount.status, EscrowStatus.Cancelled, 'Escrow should be cancelled');
+      assert.isAbove(escrowAccount.refundPool.toNumber(), 0, 'Unvested funds should be set aside for refunds');
+      assert.equal(escrowAccount.refundedAmount.toNumber(), 0, 'Nothing is refunded until the funder claims');
+      
+      await escrowClient.claimRefund(vestingEscrow);
+      
+      const refunded = await escrowClient.getEscrow(vestingEscrow);
+      assert.equal(
+        refunded.refundedAmount.toNumber(),
+        escrowAccount.refundPool.toNumber(),
+        'The sole funder should receive the whole refund pool'
+      );
+      assert.equal(
+        refunded.releasedAmount.toNumber() + refunded.refundedAmount.toNumber(),
+        100_000,
+        'Every deposited token should be accounted for'
+      );
+    });
+  });
+
+  describe('Crowdfunding', () => {
+    let tokenClient: Token;
+    let backerClient: DapprClient;
+    let walletTokenAccount: PublicKey;
+    let backerTokenAccount: PublicKey;
+
+    const balanceOf = async (account: PublicKey) =>
+      (await tokenClient.getAccountInfo(account)).amount.toNumber();
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      backerClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer2), {})
+      );
+      
+      walletTokenAccount = (await tokenClient.getOrCreateAssociatedAccountInfo(provider.wallet.publicKey)).address;
+      backerTokenAccount = (await tokenClient.getOrCreateAssociatedAccountInfo(signer2.publicKey)).address;
+      await tokenClient.mintTo(walletTokenAccount, creator.publicKey, [], 60_000);
+      await tokenClient.mintTo(backerTokenAccount, creator.publicKey, [], 80_000);
+    });
+
+    it('should record each funder\'s contribution and refund them pro rata on cancellation', async () => {
+      const { escrow: crowdEscrow } = await createEscrowFixture({ funding: 0 });
+      
+      await escrowClient.fundEscrow(crowdEscrow, tokenMint, 60_000);
+      await backerClient.escrow.fundEscrow(crowdEscrow, tokenMint, 20_000);
+      await backerClient.escrow.fundEscrow(crowdEscrow, tokenMint, 20_000);
+      
+      const contribution = await escrowClient.getContribution(crowdEscrow, signer2.publicKey);
+      assert.equal(contribution.amount.toNumber(), 40_000, 'Repeat deposits should accumulate');
+      
+      try {
+        await backerClient.escrow.claimRefund(crowdEscrow);
+        assert.fail('Refunds should not be claimable from an active escrow');
+      } catch (error) {
+        assert.include(String(error), 'RefundNotAvailable');
+      }
+      
+      // A quarter goes to the recipient and the rest is shared 60/40 between the funders
+      const recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      await escrowClient.proposeCancel(crowdEscrow, 2_500);
+      await recipientClient.escrow.acceptCancel(crowdEscrow, 2_500);
+      
+      const cancelled = await escrowClient.getEscrow(crowdEscrow);
+      assert.equal(cancelled.refundPool.toNumber(), 75_000, 'Unreleased funds should be set aside for funders');
+      
+      const walletBefore = await balanceOf(walletTokenAccount);
+      const backerBefore = await balanceOf(backerTokenAccount);
+      await escrowClient.claimRefund(crowdEscrow);
+      await backerClient.escrow.claimRefund(crowdEscrow);
+      
+      assert.equal(await balanceOf(walletTokenAccount) - walletBefore, 45_000, 'First funder should get 60% of the pool');
+      assert.equal(await balanceOf(backerTokenAccount) - backerBefore, 30_000, 'Second funder should get 40% of the pool');
+      
+      const [contributionPDA] = await escrowClient.getContributionPDA(crowdEscrow, signer2.publicKey);
+      assert.isNull(
+        await provider.connection.getAccountInfo(contributionPDA),
+        'Claimed contributions should be closed'
+      );
+    });
+
+    it('should refund funders once the funding deadline passes short of the goal', async () => {
+      const { escrow: crowdEscrow } = await createEscrowFixture({
+        fundingDeadline: Math.floor(Date.now() / 1000) + 3,
+        funding: 0,
+      });
+      await backerClient.escrow.fundEscrow(crowdEscrow, tokenMint, 30_000);
+      
+      await new Promise((resolve) => setTimeout(resolve, 5_000));
+      
+      try {
+        await backerClient.escrow.fundEscrow(crowdEscrow, tokenMint, 10_000);
+        assert.fail('Funding after the deadline should fail');
+      } catch (error) {
+        assert.include(String(error), 'FundingDeadlinePassed');
+      }
+      
+      const backerBefore = await balanceOf(backerTokenAccount);
+      await backerClient.escrow.claimRefund(crowdEscrow);
+      assert.equal(await balanceOf(backerTokenAccount) - backerBefore, 30_000, 'The full deposit should be refunded');
+      
+      const escrowAccount = await escrowClient.getEscrow(crowdEscrow);
+      assert.equal(escrowAccount.status, EscrowStatus.Cancelled, 'Missing the goal should cancel the escrow');
+      assert.equal(escrowAccount.refundedAmount.toNumber(), 30_000, 'Refunds should be tracked on the escrow');
+    });
+
+    it('should pool reclaimed milestones for the funders of a crowdfunded escrow', async () => {
+      const { escrow: crowdEscrow } = await createEscrowFixture({
+        milestones: [{ amount: 50_000, deadline: Math.floor(Date.now() / 1000) + 4 }, { amount: 50_000 }],
+        funding: 0,
+        gracePeriod: 1,
+      });
+      
+      await tokenClient.mintTo(walletTokenAccount, creator.publicKey, [], 60_000);
+      await tokenClient.mintTo(backerTokenAccount, creator.publicKey, [], 40_000);
+      await escrowClient.fundEscrow(crowdEscrow, tokenMint, 60_000);
+      await backerClient.escrow.fundEscrow(crowdEscrow, tokenMint, 40_000);
+      
+      await sleep(7_000);
+      
+      // The expired milestone is shared 60/40 between the funders rather than returned to the creator
+      const walletBefore = await balanceOf(walletTokenAccount);
+      await escrowClient.reclaimExpiredMilestone(crowdEscrow, 0, tokenMint);
+      assert.equal(await balanceOf(walletTokenAccount), walletBefore, 'The creator should not be paid directly');
+      
+      const escrowAccount = await escrowClient.getEscrow(crowdEscrow);
+      assert.isTrue(escrowAccount.crowdfunded, 'A second funder should mark the escrow crowdfunded');
+      assert.equal(escrowAccount.refundPool.toNumber(), 50_000, 'The reclaimed milestone should join the pool');
+      assert.equal(escrowAccount.status, EscrowStatus.InProgress, 'The next milestone should still be open');
+      
+      const backerBefore = await balanceOf(backerTokenAccount);
+      await backerClient.escrow.claimRefund(crowdEscrow);
+      await escrowClient.claimRefund(crowdEscrow);
+      assert.equal(await balanceOf(backerTokenAccount) - backerBefore, 20_000, 'Second funder should get 40% of the pool');
+      assert.equal(await balanceOf(walletTokenAccount) - walletBefore, 30_000, 'First funder should get 60% of the pool');
+      
+      const contribution = await escrowClient.getContribution(crowdEscrow, signer2.publicKey);
+      assert.equal(contribution.refunded.toNumber(), 20_000, 'The claim should be recorded on the open contribution');
+      
+      try {
+        await backerClient.escrow.claimRefund(crowdEscrow);
+        assert.fail('Nothing more should be owed until the pool grows');
+      } catch (error) {
+        assert.include(String(error), 'RefundNotAvailable');
+      }
+    });
+  });
+
+  describe('Recipient Transfer', () => {
+    let tokenClient: Token;
+    let recipientClient: DapprClient;
+    let newRecipientClient: DapprClient;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      newRecipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer3), {})
+      );
+    });
+
+    it('should pay releases to the new recipient after a transfer', async () => {
+      const { escrow: transferEscrow } = await createEscrowFixture({ milestones: [{ amount: 50_000 }] });
+      
+      try {
+        await recipientClient.escrow.transferRecipient(transferEscrow, provider.wallet.publicKey);
+        assert.fail('The creator should not be able to take over the recipient\'s rights');
+      } catch (error) {
+        assert.include(String(error), 'InvalidNewRecipient');
+      }
+      
+      await recipientClient.escrow.transferRecipient(transferEscrow, signer3.publicKey);
+      
+      const escrowAccount = await escrowClient.getEscrow(transferEscrow);
+      assert.isTrue(escrowAccount.recipient.equals(signer3.publicKey), 'Payment rights should move to the new recipient');
+      assert.isTrue(escrowAccount.initialRecipient.equals(recipient.publicKey), 'The PDA seed recipient should not change');
+      assert.isTrue(escrowAccount.payees[0].wallet.equals(signer3.publicKey), 'The payee share should follow the transfer');
+      
+      try {
+        await recipientClient.escrow.submitMilestone(transferEscrow, 0, Buffer.from('stale-recipient'));
+        assert.fail('The previous recipient should no longer act on the escrow');
+      } catch (error) {
+        assert.include(String(error), 'InvalidSubmitter');
+      }
+      
+      await newRecipientClient.escrow.submitMilestone(transferEscrow, 0, Buffer.from('transferred-deliverable'));
+      await escrowClient.approveMilestone(transferEscrow, 0);
+      
+      const newRecipientTokenAccount = await tokenClient.getOrCreateAssociatedAccountInfo(signer3.publicKey);
+      const previousBefore = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      await escrowClient.releaseFunds(transferEscrow, 0, tokenMint, signer3.publicKey);
+      
+      const newRecipientAfter = (await tokenClient.getAccountInfo(newRecipientTokenAccount.address)).amount.toNumber();
+      const previousAfter = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      assert.isAbove(newRecipientAfter - newRecipientTokenAccount.amount.toNumber(), 0, 'The new recipient should be paid');
+      assert.equal(previousAfter, previousBefore, 'The previous recipient should not be paid');
+    });
+
+    it('should require the creator to sign when the escrow asks for consent', async () => {
+      const { escrow: consentEscrow } = await createEscrowFixture({
+        transferRequiresCreator: true,
+        funding: 0,
+      });
+      
+      try {
+        await recipientClient.escrow.transferRecipient(consentEscrow, signer3.publicKey);
+        assert.fail('A transfer without the creator should fail');
+      } catch (error) {
+        assert.include(String(error), 'CreatorConsentRequired');
+      }
+      
+      await recipientClient.escrow.transferRecipient(
+        consentEscrow,
+        signer3.publicKey,
+        (provider.wallet as anchor.Wallet).payer
+      );
+      
+      const escrowAccount = await escrowClient.getEscrow(consentEscrow);
+      assert.isTrue(escrowAccount.recipient.equals(signer3.publicKey), 'The consented transfer should go through');
+    });
+  });
+
+  describe('Receipt NFT', () => {
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+    });
+
+    it('should pay releases to whoever holds the receipt', async () => {
+      const tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      const recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      
+      const { escrow: receiptEscrow } = await createEscrowFixture({ milestones: [{ amount: 50_000 }] });
+      
+      const { receiptMint } = await recipientClient.escrow.mintReceipt(
+        receiptEscrow,
+        'https://dappr-token-metadata.s3.amazonaws.com/receipt.json'
+      );
+      const escrowAccount = await escrowClient.getEscrow(receiptEscrow);
+      assert.isTrue(escrowAccount.receiptMint!.equals(receiptMint), 'The escrow should record its receipt mint');
+      
+      // The recipient sells the receipt to a financier
+      const receiptClient = new Token(provider.connection, receiptMint, TOKEN_PROGRAM_ID, recipient);
+      const recipientReceiptAccount = await receiptClient.getOrCreateAssociatedAccountInfo(recipient.publicKey);
+      const financierReceiptAccount = await receiptClient.getOrCreateAssociatedAccountInfo(signer1.publicKey);
+      await receiptClient.transfer(recipientReceiptAccount.address, financierReceiptAccount.address, recipient, [], 1);
+      
+      const { holder } = await escrowClient.getReceiptHolder(receiptMint);
+      assert.isTrue(holder.equals(signer1.publicKey), 'The financier should hold the receipt');
+      
+      await recipientClient.escrow.submitMilestone(receiptEscrow, 0, Buffer.from('receipt-deliverable'));
+      await escrowClient.approveMilestone(receiptEscrow, 0);
+      
+      const financierTokenAccount = await tokenClient.getOrCreateAssociatedAccountInfo(signer1.publicKey);
+      const recipientBefore = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      await escrowClient.releaseFunds(receiptEscrow, 0, tokenMint, recipient.publicKey);
+      
+      const financierAfter = (await tokenClient.getAccountInfo(financierTokenAccount.address)).amount.toNumber();
+      const recipientAfter = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      assert.isAbove(financierAfter - financierTokenAccount.amount.toNumber(), 0, 'The receipt holder should be paid');
+      assert.equal(recipientAfter, recipientBefore, 'The recipient should not be paid after selling the receipt');
+    });
+
+    it('should not mint a receipt when the recipient is not a payee', async () => {
+      const recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      const { escrow: unpaidEscrow } = await createEscrowFixture({
+        payees: [{ wallet: signer1.publicKey, shareBps: 10_000 }],
+        funding: 0,
+      });
+      
+      try {
+        await recipientClient.escrow.mintReceipt(
+          unpaidEscrow,
+          'https://dappr-token-metadata.s3.amazonaws.com/receipt.json'
+        );
+        assert.fail('A receipt with no payouts behind it should not be minted');
+      } catch (error) {
+        assert.include(String(error), 'ReceiptUnavailable');
+      }
+    });
+  });
+
+  describe('Milestone Amendment', () => {
+    let amendedEscrow: PublicKey;
+    let milestonePDA: PublicKey;
+    let tokenClient: Token;
+    let recipientClient: DapprClient;
+    const newDeadline = Math.floor(Date.now() / 1000) + 14 * 24 * 60 * 60;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      
+      ({ escrow: amendedEscrow, milestones: [milestonePDA] } = await createEscrowFixture({
+        milestones: [{ amount: 50_000 }],
+      }));
+    });
+
+    it('should only amend a milestone once the other party accepts the same terms', async () => {
+      await escrowClient.proposeMilestoneAmendment(amendedEscrow, 0, 80_000, newDeadline);
+      
+      try {
+        await escrowClient.acceptMilestoneAmendment(amendedEscrow, 0, 80_000, newDeadline);
+        assert.fail('The proposer should not be able to accept their own amendment');
+      } catch (error) {
+        assert.include(String(error), 'InvalidAmendmentAcceptor');
+      }
+      
+      try {
+        await recipientClient.escrow.acceptMilestoneAmendment(amendedEscrow, 0, 90_000, newDeadline);
+        assert.fail('Accepting different terms should fail');
+      } catch (error) {
+        assert.include(String(error), 'AmendmentTermsMismatch');
+      }
+      
+      await recipientClient.escrow.acceptMilestoneAmendment(amendedEscrow, 0, 80_000, newDeadline);
+      
+      const milestoneAccount = await escrowClient.getMilestone(milestonePDA);
+      assert.equal(milestoneAccount.amount.toNumber(), 80_000, 'Milestone amount should be amended');
+      assert.equal(milestoneAccount.deadline.toNumber(), newDeadline, 'Milestone deadline should be amended');
+      assert.isNull(milestoneAccount.pendingAmendment, 'The accepted proposal should be cleared');
+    });
+
+    it('should require the escrow to be topped up after an increase', async () => {
+      const escrowAccount = await escrowClient.getEscrow(amendedEscrow);
+      assert.equal(escrowAccount.committedAmount.toNumber(), 80_000, 'Commitment should follow the amendment');
+      assert.equal(escrowAccount.status, EscrowStatus.AwaitingFunding, 'Escrow should wait for the shortfall');
+      assert.equal(escrowAccount.fundingStatus, FundingStatus.PartiallyFunded, 'Escrow should be partially funded');
+      
+      const walletTokenAccount = await tokenClient.getOrCreateAssociatedAccountInfo(provider.wallet.publicKey);
+      await tokenClient.mintTo(walletTokenAccount.address, creator.publicKey, [], 30_000);
+      await escrowClient.fundEscrow(amendedEscrow, tokenMint, 30_000);
+      
+      const toppedUp = await escrowClient.getEscrow(amendedEscrow);
+      assert.equal(toppedUp.status, EscrowStatus.InProgress, 'Escrow should resume once fully funded');
+    });
+
+    it('should refund the deposits a decrease leaves uncommitted', async () => {
+      const walletTokenAccount = (await tokenClient.getOrCreateAssociatedAccountInfo(provider.wallet.publicKey)).address;
+      const walletBefore = (await tokenClient.getAccountInfo(walletTokenAccount)).amount.toNumber();
+      
+      await recipientClient.escrow.proposeMilestoneAmendment(amendedEscrow, 0, 10_000, newDeadline);
+      await escrowClient.acceptMilestoneAmendment(amendedEscrow, 0, 10_000, newDeadline);
+      
+      const walletAfter = (await tokenClient.getAccountInfo(walletTokenAccount)).amount.toNumber();
+      assert.equal(walletAfter - walletBefore, 70_000, 'The creator should get the excess back');
+      
+      const escrowAccount = await escrowClient.getEscrow(amendedEscrow);
+      assert.equal(escrowAccount.committedAmount.toNumber(), 10_000, 'Commitment should follow the amendment');
+      assert.equal(escrowAccount.amendmentRefunds.toNumber(), 70_000, 'The excess should be recorded');
+      assert.equal(escrowAccount.fundingStatus, FundingStatus.FullyFunded, 'Escrow should stay fully funded');
+      assert.equal(escrowAccount.status, EscrowStatus.InProgress, 'Escrow should keep running');
+    });
+
+    it('should let a running escrow be topped up after its funding deadline', async () => {
+      const { escrow: deadlineEscrow } = await createEscrowFixture({
+        milestones: [{ amount: 20_000, deadline: newDeadline }],
+        fundingDeadline: Math.floor(Date.now() / 1000) + 3,
+      });
+      
+      const started = await escrowClient.getEscrow(deadlineEscrow);
+      assert.isNull(started.fundingDeadline, 'Meeting the goal should clear the funding deadline');
+      
+      await sleep(5_000);
+      
+      await escrowClient.proposeMilestoneAmendment(deadlineEscrow, 0, 30_000, newDeadline);
+      await recipientClient.escrow.acceptMilestoneAmendment(deadlineEscrow, 0, 30_000, newDeadline);
+      
+      try {
+        await escrowClient.claimRefund(deadlineEscrow);
+        assert.fail('A started escrow should not be cancelled by a refund claim');
+      } catch (error) {
+        assert.include(String(error), 'RefundNotAvailable');
+      }
+      
+      const walletTokenAccount = await tokenClient.getOrCreateAssociatedAccountInfo(provider.wallet.publicKey);
+      await tokenClient.mintTo(walletTokenAccount.address, creator.publicKey, [], 10_000);
+      await escrowClient.fundEscrow(deadlineEscrow, tokenMint, 10_000);
+      
+      const toppedUp = await escrowClient.getEscrow(deadlineEscrow);
+      assert.equal(toppedUp.status, EscrowStatus.InProgress, 'Escrow should resume once topped up');
+    });
+  });
+  describe('Release Accounts', () => {
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+    });
+
+    it('should not release one escrow against another escrow\'s milestone', async () => {
+      const recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      
+      const { escrow: drainedEscrow } = await createEscrowFixture({ milestones: [{ amount: 50_000 }] });
+      const { escrow: approvedEscrow } = await createEscrowFixture({ milestones: [{ amount: 50_000 }] });
+      
+      // Only the second escrow's milestone is approved
+      await recipientClient.escrow.submitMilestone(approvedEscrow, 0, Buffer.from('approved-deliverable'));
+      await escrowClient.approveMilestone(approvedEscrow, 0);
+      
+      const [approvedMilestone] = await escrowClient.getMilestonePDA(approvedEscrow, 0);
+      const [config] = await escrowClient.getConfigPDA();
+      const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        TOKEN_PROGRAM_ID,
+        tokenMint,
+        drainedEscrow,
+        true
+      );
+      const treasuryTokenAccount = await Token.getAssociatedTokenAddress(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        TOKEN_PROGRAM_ID,
+        tokenMint,
+        treasury.publicKey
+      );
+      
+      try {
+        await dapprClient['program'].rpc.releaseFunds({
+          accounts: {
+            releaser: provider.wallet.publicKey,
+            escrow: drainedEscrow,
+            recipient: recipient.publicKey,
+            milestone: approvedMilestone,
+            tokenMint,
+            escrowTokenAccount,
+            config,
+            treasury: treasury.publicKey,
+            treasuryTokenAccount,
+            receiptTokenAccount: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            clock: web3.SYSVAR_CLOCK_PUBKEY,
+          },
+          remainingAccounts: [
+            { pubkey: recipientTokenAccount, isWritable: true, isSigner: false },
+          ],
+        });
+        assert.fail('Releasing with another escrow\'s milestone should fail');
+      } catch (error) {
+        assert.include(String(error), 'ConstraintSeeds');
+      }
+      
+      const drainedAccount = await escrowClient.getEscrow(drainedEscrow);
+      assert.equal(drainedAccount.releasedAmount.toNumber(), 0, 'Nothing should leave the unapproved escrow');
+    });
+  });
+
+  describe('Disputes', () => {
+    let tokenClient: Token;
+    let recipientClient: DapprClient;
+    let arbitratorClient: DapprClient;
+    let outsiderClient: DapprClient;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      await airdropSol(provider.connection, arbitrator, 1);
+      recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      arbitratorClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(arbitrator), {})
+      );
+      outsiderClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer2), {})
+      );
+      
+      ({ escrow: disputeEscrow } = await createEscrowFixture());
+      await recipientClient.escrow.submitMilestone(disputeEscrow, 0, Buffer.from('disputed-deliverable'));
+    });
+
+    it('should only let the creator or recipient raise a dispute', async () => {
+      try {
+        await outsiderClient.escrow.raiseDispute(disputeEscrow, 0);
+        assert.fail('An outsider should not be able to raise a dispute');
+      } catch (error) {
+        assert.include(String(error), 'NotEscrowParty');
+      }
+      
+      const { dispute } = await escrowClient.raiseDispute(disputeEscrow, 0);
+      
+      const disputeAccount = await escrowClient.getDispute(dispute);
+      assert.isTrue(disputeAccount.raisedBy.equals(provider.wallet.publicKey), 'The creator should have raised the dispute');
+      const escrowAccount = await escrowClient.getEscrow(disputeEscrow);
+      assert.equal(escrowAccount.status, EscrowStatus.Disputed, 'Escrow should be disputed');
+    });
+
+    it('should only accept evidence from the creator or recipient', async () => {
+      const creatorEvidence = Array.from({ length: 32 }, () => 1);
+      const recipientEvidence = Array.from({ length: 32 }, () => 2);
+      
+      try {
+        await outsiderClient.escrow.submitEvidence(disputeEscrow, 0, Array.from({ length: 32 }, () => 3));
+        assert.fail('An outsider should not be able to submit evidence');
+      } catch (error) {
+        assert.include(String(error), 'NotEscrowParty');
+      }
+      
+      await escrowClient.submitEvidence(disputeEscrow, 0, creatorEvidence);
+      await recipientClient.escrow.submitEvidence(disputeEscrow, 0, recipientEvidence);
+      
+      const [milestonePDA] = await escrowClient.getMilestonePDA(disputeEscrow, 0);
+      const [dispute] = await escrowClient.getDisputePDA(milestonePDA);
+      const disputeAccount = await escrowClient.getDispute(dispute);
+      assert.deepEqual(disputeAccount.creatorEvidence, creatorEvidence, 'Creator evidence should be recorded');
+      assert.deepEqual(disputeAccount.recipientEvidence, recipientEvidence, 'Recipient evidence should be recorded');
+    });
+
+    it('should only let the escrow arbitrator resolve the dispute', async () => {
+      try {
+        await outsiderClient.escrow.resolveDispute(disputeEscrow, 0, 10_000);
+        assert.fail('Only the escrow arbitrator should resolve the dispute');
+      } catch (error) {
+        assert.include(String(error), 'InvalidArbitrator');
+      }
+    });
+
+    it('should split the milestone amount by the ruling\'s basis points', async () => {
+      const walletTokenAccount = await Token.getAssociatedTokenAddress(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        TOKEN_PROGRAM_ID,
+        tokenMint,
+        provider.wallet.publicKey
+      );
+      const recipientBefore = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      const creatorBefore = (await tokenClient.getAccountInfo(walletTokenAccount)).amount.toNumber();
+      
+      // A quarter of the milestone goes to the recipient, the rest back to the creator
+      await arbitratorClient.escrow.resolveDispute(disputeEscrow, 0, 2_500);
+      
+      const recipientAfter = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      const creatorAfter = (await tokenClient.getAccountInfo(walletTokenAccount)).amount.toNumber();
+      assert.equal(recipientAfter - recipientBefore, 25_000, 'Recipient should receive 25% of the milestone');
+      assert.equal(creatorAfter - creatorBefore, 75_000, 'Creator should receive the remaining 75%');
+      
+      const [milestonePDA] = await escrowClient.getMilestonePDA(disputeEscrow, 0);
+      const [dispute] = await escrowClient.getDisputePDA(milestonePDA);
+      const disputeAccount = await escrowClient.getDispute(dispute);
+      assert.equal(disputeAccount.recipientShareBps, 2_500, 'The ruling should be recorded');
+      assert.ok(disputeAccount.resolvedAt, 'Resolved at should be set');
+      
+      const milestoneAccount = await escrowClient.getMilestone(milestonePDA);
+      assert.isFalse(milestoneAccount.disputed, 'Milestone should no longer be disputed');
+      assert.isTrue(milestoneAccount.verifiedBy!.equals(arbitrator.publicKey), 'The arbitrator should settle the milestone');
+      
+      const escrowAccount = await escrowClient.getEscrow(disputeEscrow);
+      assert.equal(escrowAccount.status, EscrowStatus.Completed, 'Settling the last milestone should complete the escrow');
+    });
+  });
+
+  describe('Review Period', () => {
+    let reviewEscrow: PublicKey;
+    let tokenClient: Token;
+    let recipientClient: DapprClient;
+
+    before(async () => {
+      await escrowClient.updateConfig(250, treasury.publicKey); // 2.5%
+      
+      tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      
+      ({ escrow: reviewEscrow } = await createEscrowFixture({ reviewPeriod: 3 }));
+      await recipientClient.escrow.submitMilestone(reviewEscrow, 0, Buffer.from('reviewed-deliverable'));
+    });
+
+    it('should not release an unapproved submission during the review period', async () => {
+      try {
+        await recipientClient.escrow.releaseFunds(reviewEscrow, 0, tokenMint, recipient.publicKey);
+        assert.fail('An unreviewed submission should not be payable yet');
+      } catch (error) {
+        assert.include(String(error), 'MilestoneNotCompleted');
+      }
+    });
+
+    it('should stop the creator rejecting or disputing once the review period has elapsed', async () => {
+      await sleep(5_000);
+      
+      try {
+        await escrowClient.rejectMilestone(reviewEscrow, 0, 1);
+        assert.fail('A lapsed submission should not be rejected');
+      } catch (error) {
+        assert.include(String(error), 'ReviewPeriodElapsed');
+      }
+      
+      try {
+        await escrowClient.raiseDispute(reviewEscrow, 0);
+        assert.fail('A lapsed submission should not be disputed by the creator');
+      } catch (error) {
+        assert.include(String(error), 'ReviewPeriodElapsed');
+      }
+    });
+
+    it('should deem an unreviewed submission accepted and let anyone release it', async () => {
+      const recipientBefore = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      
+      await recipientClient.escrow.releaseFunds(reviewEscrow, 0, tokenMint, recipient.publicKey);
+      
+      const recipientAfter = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      assert.equal(recipientAfter - recipientBefore, 97_500, 'Recipient should be paid net of the 2.5% fee');
+      
+      const [milestonePDA] = await escrowClient.getMilestonePDA(reviewEscrow, 0);
+      const milestoneAccount = await escrowClient.getMilestone(milestonePDA);
+      assert.isTrue(milestoneAccount.verified, 'The submission should be deemed accepted');
+      assert.isNull(milestoneAccount.verifiedBy, 'No verifier should be recorded for a deemed acceptance');
+    });
+  });
+
+  describe('Expired Milestones', () => {
+    let expiringEscrow: PublicKey;
+    let tokenClient: Token;
+    let walletTokenAccount: PublicKey;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      
+      // The recipient never submits before this deadline
+      ({ escrow: expiringEscrow } = await createEscrowFixture({
+        milestones: [{ amount: 100_000, deadline: Math.floor(Date.now() / 1000) + 4 }],
+        gracePeriod: 2,
+      }));
+      walletTokenAccount = (await tokenClient.getOrCreateAssociatedAccountInfo(provider.wallet.publicKey)).address;
+    });
+
+    it('should not reclaim a milestone before its deadline and grace period pass', async () => {
+      try {
+        await escrowClient.reclaimExpiredMilestone(expiringEscrow, 0, tokenMint);
+        assert.fail('A milestone still within its deadline should not be reclaimed');
+      } catch (error) {
+        assert.include(String(error), 'DeadlineNotReached');
+      }
+    });
+
+    it('should return an expired, unsubmitted milestone to the creator', async () => {
+      await sleep(8_000);
+      
+      const creatorBefore = (await tokenClient.getAccountInfo(walletTokenAccount)).amount.toNumber();
+      await escrowClient.reclaimExpiredMilestone(expiringEscrow, 0, tokenMint);
+      
+      const creatorAfter = (await tokenClient.getAccountInfo(walletTokenAccount)).amount.toNumber();
+      assert.equal(creatorAfter - creatorBefore, 100_000, 'Creator should get the milestone amount back');
+      
+      const escrowAccount = await escrowClient.getEscrow(expiringEscrow);
+      assert.equal(escrowAccount.refundedAmount.toNumber(), 100_000, 'Refunded amount should be recorded');
+      assert.equal(escrowAccount.status, EscrowStatus.Completed, 'Reclaiming the last milestone should complete the escrow');
+    });
+  });
+
+  describe('Mutual Cancellation', () => {
+    let cancelEscrow: PublicKey;
+    let recipientClient: DapprClient;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      
+      ({ escrow: cancelEscrow } = await createEscrowFixture());
+      await escrowClient.proposeCancel(cancelEscrow, 4_000);
+    });
+
+    it('should not let the proposer accept their own cancellation', async () => {
+      try {
+        await escrowClient.acceptCancel(cancelEscrow, 4_000);
+        assert.fail('The proposer should not be able to accept their own proposal');
+      } catch (error) {
+        assert.include(String(error), 'InvalidCancelAcceptor');
+      }
+    });
+
+    it('should not accept a cancellation on different terms', async () => {
+      try {
+        await recipientClient.escrow.acceptCancel(cancelEscrow, 5_000);
+        assert.fail('Accepting different terms should fail');
+      } catch (error) {
+        assert.include(String(error), 'CancelTermsMismatch');
+      }
+      
+      const escrowAccount = await escrowClient.getEscrow(cancelEscrow);
+      assert.equal(escrowAccount.status, EscrowStatus.InProgress, 'The escrow should stay open');
+      assert.equal(escrowAccount.cancelRecipientShareBps, 4_000, 'The original proposal should stand');
+    });
+  });
+
+  describe('Closing Escrows', () => {
+    let closingEscrow: PublicKey;
+    let recipientClient: DapprClient;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      
+      ({ escrow: closingEscrow } = await createEscrowFixture());
+    });
+
+    it('should not close an active escrow', async () => {
+      try {
+        await escrowClient.closeEscrow(closingEscrow);
+        assert.fail('An active escrow should not be closed');
+      } catch (error) {
+        assert.include(String(error), 'EscrowStillActive');
+      }
+    });
+
+    it('should not close a cancelled escrow before its refunds are claimed', async () => {
+      // Leave an emergency request open; its rent must come back when the escrow is closed
+      const signer1Client = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer1), {})
+      );
+      await signer1Client.escrow.initiateEmergencyWithdrawal(closingEscrow, provider.wallet.publicKey);
+      
+      await escrowClient.proposeCancel(closingEscrow, 0);
+      await recipientClient.escrow.acceptCancel(closingEscrow, 0);
+      
+      try {
+        await escrowClient.closeEscrow(closingEscrow);
+        assert.fail('An escrow still holding refunds should not be closed');
+      } catch (error) {
+        assert.include(String(error), 'EscrowNotSettled');
+      }
+    });
+
+    it('should close a settled escrow with its milestones', async () => {
+      await escrowClient.claimRefund(closingEscrow);
+      
+      const [milestonePDA] = await escrowClient.getMilestonePDA(closingEscrow, 0);
+      const [multisigPDA] = await escrowClient.getMultisigPDA(closingEscrow);
+      const multisigRent = (await provider.connection.getAccountInfo(multisigPDA))!.lamports;
+      const requesterBefore = await provider.connection.getBalance(signer1.publicKey);
+      await escrowClient.closeEscrow(closingEscrow);
+      
+      assert.isNull(await provider.connection.getAccountInfo(closingEscrow), 'Escrow account should be closed');
+      assert.isNull(await provider.connection.getAccountInfo(milestonePDA), 'Milestone account should be closed');
+      assert.isNull(await provider.connection.getAccountInfo(multisigPDA), 'Open emergency request should be closed');
+      assert.equal(
+        await provider.connection.getBalance(signer1.publicKey) - requesterBefore,
+        multisigRent,
+        'The requester should get the request\'s rent back'
+      );
+    });
+
+    it('should require every milestone and dispute to be closed with the escrow', async () => {
+      // The dispute escrow settled its only milestone through the arbitrator
+      const [milestonePDA] = await escrowClient.getMilestonePDA(disputeEscrow, 0);
+      const [disputePDA] = await escrowClient.getDisputePDA(milestonePDA);
+      const [multisigPDA] = await escrowClient.getMultisigPDA(disputeEscrow);
+      const escrowTokenAccount = await Token.getAssociatedTokenAddress(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        TOKEN_PROGRAM_ID,
+        tokenMint,
+        disputeEscrow,
+        true
+      );
+      
+      try {
+        await dapprClient['program'].rpc.closeEscrow({
+          accounts: {
+            creator: provider.wallet.publicKey,
+            escrow: disputeEscrow,
+            multisig: multisigPDA,
+            requester: null,
+            tokenMint,
+            escrowTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          },
+          remainingAccounts: [
+            { pubkey: milestonePDA, isWritable: true, isSigner: false },
+          ],
+        });
+        assert.fail('Leaving the dispute account open should fail');
+      } catch (error) {
+        assert.include(String(error), 'CloseAccountsMismatch');
+      }
+      
+      await escrowClient.closeEscrow(disputeEscrow);
+      
+      assert.isNull(await provider.connection.getAccountInfo(milestonePDA), 'Milestone account should be closed');
+      assert.isNull(await provider.connection.getAccountInfo(disputePDA), 'Dispute account should be closed');
+    });
+  });
+
+  describe('Emergency Withdrawal Objections', () => {
+    let tokenClient: Token;
+    let walletTokenAccount: PublicKey;
+    let recipientClient: DapprClient;
+    let arbitratorClient: DapprClient;
+    let signer1Client: DapprClient;
+    let signer2Client: DapprClient;
+
+    // Opens and approves a 2-of-2 emergency request on the escrow
+    const approveEmergencyRequest = async (target: PublicKey) => {
+      await signer1Client.escrow.initiateEmergencyWithdrawal(target, provider.wallet.publicKey);
+      await signer2Client.escrow.signEmergencyWithdrawal(target);
+    };
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      signer1Client = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer1), {})
+      );
+      signer2Client = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer2), {})
+      );
+      await airdropSol(provider.connection, arbitrator, 1);
+      arbitratorClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(arbitrator), {})
+      );
+      
+      walletTokenAccount = (await tokenClient.getOrCreateAssociatedAccountInfo(provider.wallet.publicKey)).address;
+    });
+
+    it('should let a vesting recipient object', async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const { escrow: vestingEscrow } = await createEscrowFixture({
+        milestones: [],
+        funding: 100_000,
+        emergencySigners: [signer1.publicKey, signer2.publicKey],
+        emergencyThreshold: 2,
+        vesting: {
+          amount: new BN(100_000),
+          startTs: new BN(now),
+          cliffTs: new BN(now),
+          endTs: new BN(now + 7 * 24 * 60 * 60),
+        },
+      });
+      await approveEmergencyRequest(vestingEscrow);
+      
+      await recipientClient.escrow.objectEmergencyWithdrawal(vestingEscrow);
+      
+      const [multisigPDA] = await escrowClient.getMultisigPDA(vestingEscrow);
+      const multisigAccount = await escrowClient.getMultisig(multisigPDA);
+      assert.isTrue(multisigAccount.objected, 'The vesting recipient should be able to object');
+      const escrowAccount = await escrowClient.getEscrow(vestingEscrow);
+      assert.equal(escrowAccount.status, EscrowStatus.Disputed, 'The objection should dispute the escrow');
+    });
+
+    it('should not execute a withdrawal while a dispute is open', async () => {
+      const { escrow: disputedEscrow } = await createEscrowFixture({
+        emergencySigners: [signer1.publicKey, signer2.publicKey],
+        emergencyThreshold: 2,
+      });
+      await recipientClient.escrow.submitMilestone(disputedEscrow, 0, Buffer.from('contested-deliverable'));
+      await escrowClient.raiseDispute(disputedEscrow, 0);
+      await approveEmergencyRequest(disputedEscrow);
+      
+      try {
+        await escrowClient.executeEmergencyWithdrawal(disputedEscrow);
+        assert.fail('A disputed escrow should wait for the arbitrator');
+      } catch (error) {
+        assert.include(String(error), 'EscrowDisputed');
+      }
+      
+      // The recipient can still object while the dispute is open
+      await recipientClient.escrow.objectEmergencyWithdrawal(disputedEscrow);
+      const [multisigPDA] = await escrowClient.getMultisigPDA(disputedEscrow);
+      const multisigAccount = await escrowClient.getMultisig(multisigPDA);
+      assert.isTrue(multisigAccount.objected, 'The objection should be recorded');
+      assert.isFalse(multisigAccount.pausedEscrow, 'The milestone dispute already holds the escrow');
+    });
+
+    it('should pay the creator once the arbitrator dismisses the objection and the delay passes', async () => {
+      const { escrow: withdrawnEscrow } = await createEscrowFixture({
+        emergencySigners: [signer1.publicKey, signer2.publicKey],
+        emergencyThreshold: 2,
+        emergencyDelay: 5,
+      });
+      await approveEmergencyRequest(withdrawnEscrow);
+      
+      await recipientClient.escrow.objectEmergencyWithdrawal(withdrawnEscrow);
+      await arbitratorClient.escrow.resolveEmergencyObjection(withdrawnEscrow, false);
+      
+      await sleep(6_000);
+      
+      const creatorBefore = (await tokenClient.getAccountInfo(walletTokenAccount)).amount.toNumber();
+      await escrowClient.executeEmergencyWithdrawal(withdrawnEscrow);
+      const creatorAfter = (await tokenClient.getAccountInfo(walletTokenAccount)).amount.toNumber();
+      assert.equal(creatorAfter - creatorBefore, 100_000, 'The creator should get the unreleased funds back');
+      
+      const escrowAccount = await escrowClient.getEscrow(withdrawnEscrow);
+      assert.equal(escrowAccount.status, EscrowStatus.EmergencyWithdrawn, 'The escrow should be withdrawn');
+    });
+  });
+
+  describe('Receipt Holder Rights', () => {
+    let heldEscrow: PublicKey;
+    let tokenClient: Token;
+    let recipientClient: DapprClient;
+    let financierClient: DapprClient;
+    let arbitratorClient: DapprClient;
+    let financierTokenAccount: PublicKey;
+
+    before(async () => {
+      await escrowClient.updateConfig(0, treasury.publicKey);
+      
+      tokenClient = new Token(provider.connection, tokenMint, TOKEN_PROGRAM_ID, creator);
+      await airdropSol(provider.connection, arbitrator, 1);
+      recipientClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(recipient), {})
+      );
+      financierClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(signer1), {})
+      );
+      arbitratorClient = new DapprClient(
+        new AnchorProvider(provider.connection, new anchor.Wallet(arbitrator), {})
+      );
+      
+      ({ escrow: heldEscrow } = await createEscrowFixture({
+        milestones: [{ amount: 50_000 }, { amount: 50_000 }],
+        emergencySigners: [signer2.publicKey],
+      }));
+      
+      // The recipient sells the receipt to a financier
+      const { receiptMint } = await recipientClient.escrow.mintReceipt(
+        heldEscrow,
+        'https://dappr-token-metadata.s3.amazonaws.com/receipt.json'
+      );
+      const receiptClient = new Token(provider.connection, receiptMint, TOKEN_PROGRAM_ID, recipient);
+      const recipientReceiptAccount = await receiptClient.getOrCreateAssociatedAccountInfo(recipient.publicKey);
+      const financierReceiptAccount = await receiptClient.getOrCreateAssociatedAccountInfo(signer1.publicKey);
+      await receiptClient.transfer(recipientReceiptAccount.address, financierReceiptAccount.address, recipient, [], 1);
+      
+      financierTokenAccount = (await tokenClient.getOrCreateAssociatedAccountInfo(signer1.publicKey)).address;
+    });
+
+    it('should not let the recipient transfer rights they sold with the receipt', async () => {
+      try {
+        await recipientClient.escrow.transferRecipient(heldEscrow, signer3.publicKey);
+        assert.fail('Rights held through a receipt should not be transferable');
+      } catch (error) {
+        assert.include(String(error), 'ReceiptOutstanding');
+      }
+    });
+
+    it('should pay a dispute award to the receipt holder', async () => {
+      await recipientClient.escrow.submitMilestone(heldEscrow, 0, Buffer.from('held-deliverable'));
+      await escrowClient.raiseDispute(heldEscrow, 0);
+      
+      const financierBefore = (await tokenClient.getAccountInfo(financierTokenAccount)).amount.toNumber();
+      const recipientBefore = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      await arbitratorClient.escrow.resolveDispute(heldEscrow, 0, 10_000);
+      
+      const financierAfter = (await tokenClient.getAccountInfo(financierTokenAccount)).amount.toNumber();
+      const recipientAfter = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      assert.equal(financierAfter - financierBefore, 50_000, 'The receipt holder should receive the award');
+      assert.equal(recipientAfter, recipientBefore, 'The recipient should not be paid after selling the receipt');
+    });
+
+    it('should take the receipt holder\'s consent and pay them on cancellation', async () => {
+      try {
+        await recipientClient.escrow.proposeCancel(heldEscrow, 10_000);
+        assert.fail('The recipient should no longer speak for the rights');
+      } catch (error) {
+        assert.include(String(error), 'NotEscrowParty');
+      }
+      
+      await escrowClient.proposeCancel(heldEscrow, 5_000);
+      
+      try {
+        await recipientClient.escrow.acceptCancel(heldEscrow, 5_000);
+        assert.fail('Only the receipt holder should accept on the recipient\'s side');
+      } catch (error) {
+        assert.include(String(error), 'InvalidCancelAcceptor');
+      }
+      
+      const financierBefore = (await tokenClient.getAccountInfo(financierTokenAccount)).amount.toNumber();
+      const recipientBefore = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      await financierClient.escrow.acceptCancel(heldEscrow, 5_000);
+      
+      const financierAfter = (await tokenClient.getAccountInfo(financierTokenAccount)).amount.toNumber();
+      const recipientAfter = (await tokenClient.getAccountInfo(recipientTokenAccount)).amount.toNumber();
+      assert.equal(financierAfter - financierBefore, 25_000, 'The receipt holder should receive the recipient\'s share');
+      assert.equal(recipientAfter, recipientBefore, 'The recipient should not be paid after selling the receipt');
     });
   });
 });
@@ -72,9 +72,20 @@ async function main() {
 
   // Step 1: Initialize escrow
   console.log('\n🔐 Initializing escrow...');
+  const signers = [
+    creator.publicKey,
+    Keypair.generate().publicKey,
+    Keypair.generate().publicKey
+  ];
   const { escrow: escrowPubkey } = await escrowClient.initializeEscrow(
     recipient.publicKey,
-    3 // 3 milestones
+    token.publicKey,
+    {
+      milestonesCount: 3,
+      arbitrator: Keypair.generate().publicKey,
+      emergencySigners: signers,
+      emergencyThreshold: 2, // 2-of-3 emergency multisig
+    }
   );
   
   console.log('✅ Escrow initialized:', escrowPubkey.toString());
@@ -116,18 +127,22 @@ async function main() {
   console.log('\n📊 Escrow details:');
   console.log(`- Creator: ${escrowAccount.creator.toString()}`);
   console.log(`- Recipient: ${escrowAccount.recipient.toString()}`);
-  console.log(`- Total amount: ${escrowAccount.totalAmount.toNumber() / 1_000_000} tokens`);
+  console.log(`- Funded amount: ${escrowAccount.fundedAmount.toNumber() / 1_000_000} tokens`);
   console.log(`- Status: ${escrowAccount.status}`);
   console.log(`- Milestones: ${escrowAccount.milestonesCount}`);
 
-  // Step 4: Complete first milestone
-  console.log('\n✅ Completing milestone 1...');
-  await escrowClient.completeMilestone(escrowPubkey, 0);
+  // Step 4: Submit and approve first milestone
+  console.log('\n✅ Submitting and approving milestone 1...');
+  const recipientClient = new DapprClient(
+    new anchor.AnchorProvider(CONNECTION, new anchor.Wallet(recipient), {})
+  );
+  await recipientClient.escrow.submitMilestone(escrowPubkey, 0, Buffer.from('milestone-1-deliverable'));
+  await escrowClient.approveMilestone(escrowPubkey, 0);
   
-  // Verify milestone was completed
+  // Verify milestone was verified
   const [milestonePDA] = await escrowClient.getMilestonePDA(escrowPubkey, 0);
   const milestoneAccount = await escrowClient.getMilestone(milestonePDA);
-  console.log('✅ Milestone 1 completed at:', new Date(milestoneAccount.completedAt!.toNumber() * 1000).toISOString());
+  console.log('✅ Milestone 1 verified at:', new Date(milestoneAccount.verifiedAt!.toNumber() * 1000).toISOString());
 
   // Step 5: Release funds for first milestone
   console.log('\n💸 Releasing funds for milestone 1...');
@@ -149,16 +164,9 @@ async function main() {
 
   // Step 6: Initiate emergency withdrawal
   console.log('\n🚨 Initiating emergency withdrawal...');
-  const signers = [
-    Keypair.generate().publicKey,
-    Keypair.generate().publicKey,
-    Keypair.generate().publicKey
-  ];
-  
   const { multisig: multisigPubkey } = await escrowClient.initiateEmergencyWithdrawal(
     escrowPubkey,
-    creator.publicKey,
-    signers
+    creator.publicKey
   );
   
   console.log('✅ Emergency withdrawal initiated with 3 signers (2/3 required)');
@@ -170,7 +178,7 @@ async function main() {
   console.log(`- Escrow: ${multisigAccount.escrow.toString()}`);
   console.log(`- Threshold: ${multisigAccount.threshold} of ${multisigAccount.signers.length}`);
   console.log('- Signers:', multisigAccount.signers.map(s => s.toString()));
-  console.log('- Signatures:', multisigAccount.signatures);
+  console.log('- Signed by:', multisigAccount.signedBy.map(s => s.toString()));
 }
 
 main().catch(console.error);